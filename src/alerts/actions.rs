@@ -0,0 +1,357 @@
+use super::rule::parse_duration;
+use super::{AlertEvent, EventKind};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs::OpenOptions;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const ACTION_LOG_SIZE: usize = 50;
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_MIN_INTERVAL: u64 = 60;
+
+/// Something to do when an alert fires or resolves, from `[[alerts.actions]]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertAction {
+    #[serde(flatten)]
+    pub kind: ActionKind,
+    /// Which transitions trigger the action: `fire`, `resolve` or both (the default).
+    #[serde(default = "default_triggers")]
+    pub on: Vec<Trigger>,
+    /// Minimum time between two runs of this action, e.g. `"5m"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_interval: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ActionKind {
+    /// Run through `sh -c` (`cmd /C` on Windows) with `ALERT_*` env vars set.
+    Command { command: String },
+    /// Append a line describing the event to a file.
+    File { path: String },
+    /// POST the event as JSON to a plain `http://` URL.
+    Webhook { url: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Trigger {
+    Fire,
+    Resolve,
+}
+
+fn default_triggers() -> Vec<Trigger> {
+    vec![Trigger::Fire, Trigger::Resolve]
+}
+
+impl AlertAction {
+    /// The `min_interval` as written, when it isn't a valid duration.
+    pub fn invalid_min_interval(&self) -> Option<&str> {
+        self.min_interval
+            .as_deref()
+            .filter(|interval| parse_duration(interval).is_none())
+    }
+
+    fn min_interval(&self) -> Duration {
+        let secs = self
+            .min_interval
+            .as_deref()
+            .and_then(parse_duration)
+            .unwrap_or(DEFAULT_MIN_INTERVAL);
+        Duration::from_secs(secs)
+    }
+
+    fn describe(&self) -> String {
+        match &self.kind {
+            ActionKind::Command { command } => format!("run `{}`", command),
+            ActionKind::File { path } => format!("append to {}", path),
+            ActionKind::Webhook { url } => format!("POST {}", url),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Payload<'a> {
+    alert: &'a str,
+    state: &'a str,
+    condition: String,
+    metric: String,
+    value: Option<f32>,
+    threshold: f32,
+    duration_secs: u64,
+    host: &'a str,
+    timestamp: String,
+}
+
+/// A timestamped line in the action log.
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub at: chrono::DateTime<chrono::Local>,
+    pub message: String,
+}
+
+/// Runs rule actions for alert events, rate limiting each action and keeping
+/// a log of what was (or in dry-run mode, would have been) done.
+pub struct ActionRunner {
+    dry_run: bool,
+    hostname: String,
+    /// When each action of each rule last ran, per trigger, so a resolve
+    /// soon after a fire still goes out. Keyed by rule name and action
+    /// rather than position so a reload that reorders rules keeps the limits.
+    last_run: HashMap<(String, String, Trigger), Instant>,
+    log: Arc<Mutex<VecDeque<LogEntry>>>,
+}
+
+impl ActionRunner {
+    pub fn new(dry_run: bool, hostname: String) -> Self {
+        Self {
+            dry_run,
+            hostname,
+            last_run: HashMap::new(),
+            log: Arc::new(Mutex::new(VecDeque::with_capacity(ACTION_LOG_SIZE))),
+        }
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    pub fn log(&self) -> Vec<LogEntry> {
+        self.log
+            .lock()
+            .map(|l| l.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn dispatch(&mut self, event: &AlertEvent, now: Instant) {
+        let trigger = match event.kind {
+            EventKind::Fired => Trigger::Fire,
+            EventKind::Resolved => Trigger::Resolve,
+        };
+
+        for action in &event.rule.actions {
+            if !action.on.contains(&trigger) {
+                continue;
+            }
+
+            let key = (event.rule.name.clone(), action.describe(), trigger);
+            if let Some(last) = self.last_run.get(&key) {
+                if now.duration_since(*last) < action.min_interval() {
+                    push_log(
+                        &self.log,
+                        format!(
+                            "{}: rate limited, skipped {}",
+                            event.rule.name,
+                            action.describe()
+                        ),
+                    );
+                    continue;
+                }
+            }
+            self.last_run.insert(key, now);
+
+            if self.dry_run {
+                push_log(
+                    &self.log,
+                    format!(
+                        "[dry-run] {} {}: would {}",
+                        event.rule.name,
+                        event.kind.as_str(),
+                        action.describe()
+                    ),
+                );
+                continue;
+            }
+
+            self.run(action, event);
+        }
+    }
+
+    fn run(&self, action: &AlertAction, event: &AlertEvent) {
+        let payload = self.payload(event);
+        let name = event.rule.name.clone();
+        let log = Arc::clone(&self.log);
+
+        match &action.kind {
+            ActionKind::Command { command } => {
+                let mut cmd = shell_command(command);
+                cmd.stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .env("ALERT_NAME", payload.alert)
+                    .env("ALERT_STATE", payload.state)
+                    .env("ALERT_CONDITION", &payload.condition)
+                    .env("ALERT_METRIC", &payload.metric)
+                    .env(
+                        "ALERT_VALUE",
+                        payload.value.map(|v| v.to_string()).unwrap_or_default(),
+                    )
+                    .env("ALERT_THRESHOLD", payload.threshold.to_string())
+                    .env("ALERT_DURATION", payload.duration_secs.to_string())
+                    .env("ALERT_HOST", payload.host)
+                    .env("ALERT_TIMESTAMP", &payload.timestamp);
+
+                let command = command.clone();
+                match cmd.spawn() {
+                    // Reap the child off the UI thread so slow hooks never block a frame.
+                    Ok(mut child) => {
+                        thread::spawn(move || {
+                            let message = match child.wait() {
+                                Ok(status) if status.success() => {
+                                    format!("{}: ran `{}`", name, command)
+                                }
+                                Ok(status) => {
+                                    format!("{}: `{}` exited with {}", name, command, status)
+                                }
+                                Err(err) => format!("{}: `{}` failed: {}", name, command, err),
+                            };
+                            push_log(&log, message);
+                        });
+                    }
+                    Err(err) => push_log(
+                        &log,
+                        format!("{}: failed to run `{}`: {}", name, command, err),
+                    ),
+                }
+            }
+            ActionKind::File { path } => {
+                let line = format!(
+                    "{} {} {} ({}) value={} duration={}s\n",
+                    payload.timestamp,
+                    payload.state.to_uppercase(),
+                    payload.alert,
+                    payload.condition,
+                    payload
+                        .value
+                        .map(|v| format!("{:.2}", v))
+                        .unwrap_or_else(|| "n/a".to_string()),
+                    payload.duration_secs,
+                );
+                let path = path.clone();
+                // Off the UI thread too, so a slow or network-mounted file can't stall a frame.
+                thread::spawn(move || {
+                    let result = OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(&path)
+                        .and_then(|mut file| file.write_all(line.as_bytes()));
+                    let message = match result {
+                        Ok(()) => format!("{}: appended to {}", name, path),
+                        Err(err) => format!("{}: failed to write {}: {}", name, path, err),
+                    };
+                    push_log(&log, message);
+                });
+            }
+            ActionKind::Webhook { url } => {
+                let body = serde_json::to_string(&payload).unwrap_or_default();
+                let url = url.clone();
+                thread::spawn(move || {
+                    let message = match post_json(&url, &body) {
+                        Ok(status) => format!("{}: POST {} -> {}", name, url, status),
+                        Err(err) => format!("{}: POST {} failed: {}", name, url, err),
+                    };
+                    push_log(&log, message);
+                });
+            }
+        }
+    }
+
+    fn payload<'a>(&'a self, event: &'a AlertEvent) -> Payload<'a> {
+        let condition = &event.rule.condition;
+        Payload {
+            alert: &event.rule.name,
+            state: event.kind.as_str(),
+            condition: condition.to_string(),
+            metric: condition.metric.to_string(),
+            value: event.value,
+            threshold: condition.threshold,
+            duration_secs: event.duration.as_secs(),
+            host: &self.hostname,
+            timestamp: chrono::Local::now().to_rfc3339(),
+        }
+    }
+}
+
+fn push_log(log: &Mutex<VecDeque<LogEntry>>, message: String) {
+    if let Ok(mut log) = log.lock() {
+        log.push_back(LogEntry {
+            at: chrono::Local::now(),
+            message,
+        });
+        while log.len() > ACTION_LOG_SIZE {
+            log.pop_front();
+        }
+    }
+}
+
+fn shell_command(command: &str) -> Command {
+    if cfg!(target_os = "windows") {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+/// Minimal HTTP/1.1 POST over a plain TCP socket; returns the response status line.
+fn post_json(url: &str, body: &str) -> Result<String, String> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| "only http:// webhook URLs are supported".to_string())?;
+    let (authority, path) = match rest.find('/') {
+        Some(idx) => (&rest[..idx], &rest[idx..]),
+        None => (rest, "/"),
+    };
+    let address = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{}:80", authority)
+    };
+
+    let socket = address
+        .to_socket_addrs()
+        .map_err(|e| e.to_string())?
+        .next()
+        .ok_or_else(|| format!("could not resolve {}", authority))?;
+    let mut stream =
+        TcpStream::connect_timeout(&socket, WEBHOOK_TIMEOUT).map_err(|e| e.to_string())?;
+    stream
+        .set_read_timeout(Some(WEBHOOK_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    stream
+        .set_write_timeout(Some(WEBHOOK_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: rust-tui-monitor\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        path,
+        authority,
+        body.len(),
+        body
+    );
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|e| e.to_string())?;
+
+    let mut response = Vec::new();
+    let _ = stream.take(1024).read_to_end(&mut response);
+    let response = String::from_utf8_lossy(&response);
+    let status = response.lines().next().unwrap_or("").trim();
+    match status.split_whitespace().nth(1) {
+        Some(code) if code.starts_with('2') => Ok(status.to_string()),
+        Some(_) => Err(status.to_string()),
+        None => Err("no response".to_string()),
+    }
+}
@@ -0,0 +1,219 @@
+pub mod actions;
+pub mod notify;
+pub mod rule;
+
+pub use actions::ActionRunner;
+pub use notify::Notification;
+pub use rule::{AlertRule, Metric};
+
+use std::time::{Duration, Instant};
+
+/// How long a resolved alert stays listed before it is cleared.
+const RESOLVED_LINGER: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlertState {
+    Inactive,
+    /// The condition holds but has not held for the rule's duration yet.
+    Pending {
+        since: Instant,
+    },
+    Firing {
+        since: Instant,
+    },
+    Resolved {
+        at: Instant,
+        fired_for: Duration,
+    },
+}
+
+/// Current metric values the rules are evaluated against.
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    pub cpu: f32,
+    pub memory: f32,
+    pub swap: f32,
+    /// Used percentage keyed by mount point.
+    pub disks: Vec<(String, f32)>,
+    pub net_rx: f32,
+    pub net_tx: f32,
+}
+
+impl Metrics {
+    pub fn value(&self, metric: &Metric) -> Option<f32> {
+        match metric {
+            Metric::Cpu => Some(self.cpu),
+            Metric::Memory => Some(self.memory),
+            Metric::Swap => Some(self.swap),
+            Metric::Disk(mount) => self
+                .disks
+                .iter()
+                .find(|(m, _)| m == mount)
+                .map(|(_, used)| *used),
+            Metric::NetRx => Some(self.net_rx),
+            Metric::NetTx => Some(self.net_tx),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventKind {
+    Fired,
+    Resolved,
+}
+
+impl EventKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventKind::Fired => "firing",
+            EventKind::Resolved => "resolved",
+        }
+    }
+}
+
+/// A firing or resolving transition produced by an evaluation pass.
+#[derive(Debug, Clone)]
+pub struct AlertEvent {
+    pub rule: AlertRule,
+    pub kind: EventKind,
+    pub value: Option<f32>,
+    /// How long the condition held before firing, or how long the alert fired.
+    pub duration: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct Alert {
+    pub rule: AlertRule,
+    pub state: AlertState,
+    pub value: Option<f32>,
+}
+
+impl Alert {
+    /// How long the alert has been in its current state.
+    pub fn duration(&self, now: Instant) -> Duration {
+        match self.state {
+            AlertState::Inactive => Duration::ZERO,
+            AlertState::Pending { since } | AlertState::Firing { since } => now - since,
+            AlertState::Resolved { fired_for, .. } => fired_for,
+        }
+    }
+
+    fn step(&mut self, value: Option<f32>, now: Instant) -> Option<EventKind> {
+        self.value = value;
+        let previous = self.state;
+        let condition = &self.rule.condition;
+        let hold = Duration::from_secs(condition.duration);
+        let active = value.is_some_and(|v| condition.matches(v));
+
+        self.state = match self.state {
+            AlertState::Inactive | AlertState::Resolved { .. } if active => {
+                if hold.is_zero() {
+                    AlertState::Firing { since: now }
+                } else {
+                    AlertState::Pending { since: now }
+                }
+            }
+            AlertState::Pending { since } if active => {
+                if now - since >= hold {
+                    AlertState::Firing { since: now }
+                } else {
+                    AlertState::Pending { since }
+                }
+            }
+            AlertState::Pending { .. } => AlertState::Inactive,
+            AlertState::Firing { since } => {
+                let cleared = match value {
+                    Some(v) => condition.cleared(v, self.rule.hysteresis()),
+                    None => true,
+                };
+                if cleared {
+                    AlertState::Resolved {
+                        at: now,
+                        fired_for: now - since,
+                    }
+                } else {
+                    AlertState::Firing { since }
+                }
+            }
+            AlertState::Resolved { at, .. } if now - at >= RESOLVED_LINGER => AlertState::Inactive,
+            state => state,
+        };
+
+        match (previous, self.state) {
+            (AlertState::Firing { .. }, AlertState::Firing { .. }) => None,
+            (_, AlertState::Firing { .. }) => Some(EventKind::Fired),
+            (AlertState::Firing { .. }, AlertState::Resolved { .. }) => Some(EventKind::Resolved),
+            _ => None,
+        }
+    }
+}
+
+pub struct AlertEngine {
+    pub alerts: Vec<Alert>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: &[AlertRule]) -> Self {
+        Self {
+            alerts: rules
+                .iter()
+                .map(|rule| Alert {
+                    rule: rule.clone(),
+                    state: AlertState::Inactive,
+                    value: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Replaces the rules, keeping the state of any whose name and condition
+    /// are unchanged so a reload doesn't reset pending or firing alerts.
+    pub fn set_rules(&mut self, rules: &[AlertRule]) {
+        let mut previous = std::mem::take(&mut self.alerts);
+        self.alerts = rules
+            .iter()
+            .map(|rule| {
+                let kept = previous
+                    .iter()
+                    .position(|a| a.rule.name == rule.name && a.rule.condition == rule.condition)
+                    .map(|index| previous.remove(index));
+                Alert {
+                    rule: rule.clone(),
+                    state: kept.as_ref().map_or(AlertState::Inactive, |a| a.state),
+                    value: kept.and_then(|a| a.value),
+                }
+            })
+            .collect();
+    }
+
+    pub fn evaluate(&mut self, metrics: &Metrics, now: Instant) -> Vec<AlertEvent> {
+        let mut events = Vec::new();
+        for alert in &mut self.alerts {
+            let value = metrics.value(&alert.rule.condition.metric);
+            let pending_for = match alert.state {
+                AlertState::Pending { since } => now - since,
+                _ => Duration::ZERO,
+            };
+
+            if let Some(kind) = alert.step(value, now) {
+                let duration = match kind {
+                    EventKind::Fired => pending_for,
+                    EventKind::Resolved => alert.duration(now),
+                };
+                events.push(AlertEvent {
+                    rule: alert.rule.clone(),
+                    kind,
+                    value,
+                    duration,
+                });
+            }
+        }
+        events
+    }
+
+    pub fn firing(&self) -> impl Iterator<Item = &Alert> {
+        self.alerts
+            .iter()
+            .filter(|a| matches!(a.state, AlertState::Firing { .. }))
+    }
+}
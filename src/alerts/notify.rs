@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Ways a firing alert can get the user's attention when the terminal is not focused.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Notifier {
+    /// Terminal bell (BEL).
+    Bell,
+    /// OSC 9 notification (iTerm2, Windows Terminal, kitty, foot, ...).
+    Osc9,
+    /// OSC 777 notification (rxvt-unicode, Konsole, VTE based terminals).
+    Osc777,
+    /// Freedesktop notification through `notify-send`.
+    Desktop,
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub notifier: Notifier,
+    pub title: String,
+    pub body: String,
+}
+
+impl Notification {
+    /// The escape sequence to write to the terminal, if this notifier uses one.
+    pub fn escape_sequence(&self) -> Option<String> {
+        let title = sanitize(&self.title);
+        let body = sanitize(&self.body);
+        match self.notifier {
+            Notifier::Bell => Some("\x07".to_string()),
+            Notifier::Osc9 => Some(format!("\x1b]9;{}: {}\x07", title, body)),
+            Notifier::Osc777 => Some(format!(
+                "\x1b]777;notify;{};{}\x07",
+                title.replace(';', ","),
+                body
+            )),
+            Notifier::Desktop => None,
+        }
+    }
+
+    /// Sends notifiers that don't go through the terminal.
+    pub fn send_external(&self) {
+        if self.notifier != Notifier::Desktop {
+            return;
+        }
+
+        let child = Command::new("notify-send")
+            .args([
+                "--app-name=rust-tui-monitor",
+                "--urgency=critical",
+                &self.title,
+                &self.body,
+            ])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+
+        // notify-send missing is fine: desktop notifications are best effort.
+        if let Ok(mut child) = child {
+            thread::spawn(move || {
+                let _ = child.wait();
+            });
+        }
+    }
+}
+
+/// Strips control characters so alert text can't terminate or inject escape sequences.
+fn sanitize(text: &str) -> String {
+    text.chars().filter(|c| !c.is_control()).collect()
+}
@@ -0,0 +1,274 @@
+use super::actions::AlertAction;
+use super::notify::Notifier;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// An alert rule as written in the `[[alerts]]` config section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub name: String,
+    pub condition: Condition,
+    /// How far the value has to move back past the threshold before a firing
+    /// alert resolves. Defaults to 5% of the threshold.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hysteresis: Option<f32>,
+    /// Notifications sent when the rule fires while the terminal is unfocused.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notify: Vec<Notifier>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<AlertAction>,
+}
+
+impl AlertRule {
+    pub fn hysteresis(&self) -> f32 {
+        self.hysteresis
+            .unwrap_or(self.condition.threshold.abs() * 0.05)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Metric {
+    Cpu,
+    Memory,
+    Swap,
+    /// Used percentage of the disk mounted at this path.
+    Disk(String),
+    /// Download rate in MB/s.
+    NetRx,
+    /// Upload rate in MB/s.
+    NetTx,
+}
+
+impl Metric {
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Metric::NetRx | Metric::NetTx => " MB/s",
+            _ => "%",
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Metric::Cpu => write!(f, "cpu"),
+            Metric::Memory => write!(f, "memory"),
+            Metric::Swap => write!(f, "swap"),
+            Metric::Disk(mount) => write!(f, "disk {} used", mount),
+            Metric::NetRx => write!(f, "net rx"),
+            Metric::NetTx => write!(f, "net tx"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Above,
+    AtLeast,
+    Below,
+    AtMost,
+}
+
+impl Comparison {
+    fn parse(token: &str) -> Option<Self> {
+        match token {
+            ">" => Some(Self::Above),
+            ">=" => Some(Self::AtLeast),
+            "<" => Some(Self::Below),
+            "<=" => Some(Self::AtMost),
+            _ => None,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Above => ">",
+            Self::AtLeast => ">=",
+            Self::Below => "<",
+            Self::AtMost => "<=",
+        }
+    }
+}
+
+/// A parsed rule condition such as `cpu > 90 for 30s` or `disk / used > 95`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Condition {
+    pub metric: Metric,
+    pub comparison: Comparison,
+    pub threshold: f32,
+    /// Seconds the condition must hold before the alert fires.
+    pub duration: u64,
+}
+
+impl Condition {
+    pub fn matches(&self, value: f32) -> bool {
+        match self.comparison {
+            Comparison::Above => value > self.threshold,
+            Comparison::AtLeast => value >= self.threshold,
+            Comparison::Below => value < self.threshold,
+            Comparison::AtMost => value <= self.threshold,
+        }
+    }
+
+    /// Whether `value` is far enough back on the safe side of the threshold
+    /// for a firing alert to resolve.
+    pub fn cleared(&self, value: f32, hysteresis: f32) -> bool {
+        match self.comparison {
+            Comparison::Above | Comparison::AtLeast => value < self.threshold - hysteresis,
+            Comparison::Below | Comparison::AtMost => value > self.threshold + hysteresis,
+        }
+    }
+}
+
+impl TryFrom<String> for Condition {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+
+        let op_index = tokens
+            .iter()
+            .position(|t| Comparison::parse(t).is_some())
+            .ok_or_else(|| format!("condition `{}` has no comparison (>, >=, <, <=)", text))?;
+        let comparison = Comparison::parse(tokens[op_index]).unwrap();
+
+        let metric = parse_metric(&tokens[..op_index])
+            .ok_or_else(|| format!("unknown metric in condition `{}`", text))?;
+
+        let threshold = tokens
+            .get(op_index + 1)
+            .map(|t| t.trim_end_matches('%'))
+            .and_then(|t| t.parse::<f32>().ok())
+            .ok_or_else(|| format!("missing or invalid threshold in condition `{}`", text))?;
+
+        let duration = match &tokens[op_index + 2..] {
+            [] => 0,
+            ["for", duration] => parse_duration(duration).ok_or_else(|| {
+                format!("invalid duration `{}` in condition `{}`", duration, text)
+            })?,
+            _ => return Err(format!("unexpected trailing text in condition `{}`", text)),
+        };
+
+        Ok(Self {
+            metric,
+            comparison,
+            threshold,
+            duration,
+        })
+    }
+}
+
+impl From<Condition> for String {
+    fn from(condition: Condition) -> Self {
+        condition.to_string()
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.metric,
+            self.comparison.symbol(),
+            self.threshold
+        )?;
+        if self.duration > 0 {
+            write!(f, " for {}s", self.duration)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_metric(tokens: &[&str]) -> Option<Metric> {
+    match tokens {
+        ["cpu"] => Some(Metric::Cpu),
+        ["memory"] | ["mem"] | ["ram"] => Some(Metric::Memory),
+        ["swap"] => Some(Metric::Swap),
+        ["disk", mount] | ["disk", mount, "used"] => Some(Metric::Disk(mount.to_string())),
+        ["net", "rx"] | ["download"] => Some(Metric::NetRx),
+        ["net", "tx"] | ["upload"] => Some(Metric::NetTx),
+        _ => None,
+    }
+}
+
+/// Parses `30s`, `5m`, `1h` or a bare number of seconds.
+pub fn parse_duration(text: &str) -> Option<u64> {
+    let (number, multiplier) = match text.chars().last()? {
+        's' => (&text[..text.len() - 1], 1),
+        'm' => (&text[..text.len() - 1], 60),
+        'h' => (&text[..text.len() - 1], 3_600),
+        _ => (text, 1),
+    };
+    number.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn condition(text: &str) -> Result<Condition, String> {
+        Condition::try_from(text.to_string())
+    }
+
+    #[test]
+    fn parses_conditions() {
+        let cpu = condition("cpu > 90% for 5m").unwrap();
+        assert_eq!(cpu.metric, Metric::Cpu);
+        assert_eq!(cpu.comparison, Comparison::Above);
+        assert_eq!(cpu.threshold, 90.0);
+        assert_eq!(cpu.duration, 300);
+
+        let disk = condition("disk / used >= 95").unwrap();
+        assert_eq!(disk.metric, Metric::Disk("/".to_string()));
+        assert_eq!(disk.comparison, Comparison::AtLeast);
+        assert_eq!(disk.duration, 0);
+
+        let rx = condition("download <= 1.5 for 30").unwrap();
+        assert_eq!(rx.metric, Metric::NetRx);
+        assert_eq!(rx.threshold, 1.5);
+        assert_eq!(rx.duration, 30);
+    }
+
+    #[test]
+    fn conditions_round_trip_through_display() {
+        let text = condition("mem < 10 for 1h").unwrap().to_string();
+        assert_eq!(text, "memory < 10 for 3600s");
+        assert_eq!(condition(&text).unwrap().duration, 3_600);
+    }
+
+    #[test]
+    fn rejects_bad_conditions() {
+        assert!(condition("cpu 90").unwrap_err().contains("no comparison"));
+        assert!(condition("gpu > 90")
+            .unwrap_err()
+            .contains("unknown metric"));
+        assert!(condition("cpu > lots").unwrap_err().contains("threshold"));
+        assert!(condition("cpu >").unwrap_err().contains("threshold"));
+        assert!(condition("cpu > 90 for ever")
+            .unwrap_err()
+            .contains("duration"));
+        assert!(condition("cpu > 90 during 5m")
+            .unwrap_err()
+            .contains("trailing"));
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("30s"), Some(30));
+        assert_eq!(parse_duration("5m"), Some(300));
+        assert_eq!(parse_duration("2h"), Some(7_200));
+        assert_eq!(parse_duration("45"), Some(45));
+    }
+
+    #[test]
+    fn rejects_bad_durations() {
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("m"), None);
+        assert_eq!(parse_duration("5d"), None);
+        assert_eq!(parse_duration("-5s"), None);
+        assert_eq!(parse_duration("1.5m"), None);
+        assert_eq!(parse_duration("99999999999999999h"), None);
+    }
+}
@@ -1,61 +1,621 @@
-use crate::config::Config;
-use crate::system::{NetworkStats, SystemInfo, SystemMonitor};
-use std::time::Instant;
-
-pub struct App {
-    pub config: Config,
-    pub system_info: SystemInfo,
-    pub monitor: SystemMonitor,
-    pub network: NetworkStats,
-    pub last_refresh: Instant,
-    pub should_quit: bool,
-    pub current_theme_index: usize,
-}
-
-impl App {
-    pub fn new(config: Config) -> Self {
-        Self {
-            config,
-            system_info: SystemInfo::new(),
-            monitor: SystemMonitor::new(),
-            network: NetworkStats::new(),
-            last_refresh: Instant::now(),
-            should_quit: false,
-            current_theme_index: 0,
-        }
-    }
-
-    pub fn update(&mut self) {
-        let now = Instant::now();
-        let elapsed = now.duration_since(self.last_refresh);
-
-        if elapsed.as_millis() >= self.config.refresh_rate as u128 {
-            self.monitor.refresh();
-            self.monitor.update_cpu_history();
-            self.network.refresh();
-            self.last_refresh = now;
-        }
-    }
-
-    pub fn quit(&mut self) {
-        self.should_quit = true;
-    }
-
-    pub fn force_refresh(&mut self) {
-        self.monitor.refresh();
-        self.monitor.update_cpu_history();
-        self.network.refresh();
-        self.last_refresh = Instant::now();
-    }
-
-    pub fn cycle_theme(&mut self) {
-        let themes = [
-            "default", "nord", "gruvbox", "dracula", "monokai",
-            "cyberpunk", "tokyo-night", "solarized-dark", "solarized-light",
-            "one-dark", "material", "ayu-dark", "rosepine"
-        ];
-        self.current_theme_index = (self.current_theme_index + 1) % themes.len();
-        self.config.theme = crate::theme::Theme::from_name(themes[self.current_theme_index]);
-    }
-}
-
+use crate::alerts::{ActionRunner, AlertEngine, EventKind, Metrics, Notification};
+use crate::config::{Config, ConfigWatcher};
+use crate::keys::{Action, Keymap};
+use crate::layout::{Panel, Screen};
+use crate::mouse::{ChartDrag, Regions};
+use crate::system::cgroups::ContainerLimits;
+use crate::system::history::{unix_now, TimeSeries};
+use crate::system::monitor::{ProcessColumn, ProcessSort};
+use crate::system::{Collection, Collector, MetricsHistory, Scheduler, Snapshot, SystemInfo};
+use crate::theme::ThemeChoice;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Time windows the history charts can be zoomed between, in seconds.
+pub const CHART_WINDOWS: [u64; 5] = [60, 300, 900, 3_600, 86_400];
+
+/// Shortest range a chart selection can zoom to, in seconds.
+const MIN_SELECTION: u64 = 10;
+
+/// Refresh rates the faster/slower keys step through, in milliseconds.
+const REFRESH_RATES: [u64; 8] = [100, 250, 500, 1_000, 2_000, 5_000, 10_000, 30_000];
+
+/// Which slice of the metrics history the charts are showing.
+#[derive(Debug, Clone, Default)]
+pub struct ChartView {
+    pub window_index: usize,
+    /// Seconds between the newest sample and the right edge of the chart.
+    pub offset: u64,
+    /// Seconds between the right edge of the chart and the readout cursor.
+    pub cursor: Option<u64>,
+    /// A window picked by dragging over a chart, overriding `window_index`.
+    pub span: Option<u64>,
+}
+
+impl ChartView {
+    pub fn window(&self) -> u64 {
+        self.span.unwrap_or(CHART_WINDOWS[self.window_index])
+    }
+
+    pub fn window_label(&self) -> String {
+        format_span(self.window())
+    }
+
+    pub fn is_live(&self) -> bool {
+        self.offset == 0
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.span = None;
+        self.window_index = self.window_index.saturating_sub(1);
+        self.clamp_cursor();
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.span = None;
+        self.window_index = (self.window_index + 1).min(CHART_WINDOWS.len() - 1);
+        self.clamp_cursor();
+    }
+
+    pub fn scroll_back(&mut self, max_offset: u64) {
+        self.offset = (self.offset + self.window() / 4).min(max_offset);
+    }
+
+    pub fn scroll_forward(&mut self) {
+        self.offset = self.offset.saturating_sub(self.window() / 4);
+    }
+
+    pub fn go_live(&mut self) {
+        self.offset = 0;
+        self.span = None;
+        self.clamp_cursor();
+    }
+
+    /// Shows exactly the range between two timestamps, `newest` being the
+    /// latest sample the chart was drawn with.
+    pub fn select(&mut self, start: u64, end: u64, newest: u64) {
+        let (start, end) = (start.min(end), start.max(end));
+        self.span = Some((end - start).max(MIN_SELECTION));
+        self.offset = newest.saturating_sub(end);
+        self.clamp_cursor();
+    }
+
+    /// Puts the cursor on the given number of seconds before the right edge.
+    pub fn place_cursor(&mut self, seconds_before_end: u64) {
+        self.cursor = Some(seconds_before_end);
+        self.clamp_cursor();
+    }
+
+    pub fn toggle_cursor(&mut self) {
+        self.cursor = match self.cursor {
+            Some(_) => None,
+            None => Some(self.window() / 2),
+        };
+    }
+
+    pub fn move_cursor(&mut self, delta: i64) {
+        if let Some(cursor) = self.cursor {
+            let step = (self.window() / 60).max(1) as i64;
+            self.cursor = Some(cursor.saturating_add_signed(-delta * step));
+            self.clamp_cursor();
+        }
+    }
+
+    fn clamp_cursor(&mut self) {
+        let window = self.window();
+        if let Some(cursor) = self.cursor.as_mut() {
+            *cursor = (*cursor).min(window);
+        }
+    }
+}
+
+pub fn format_span(seconds: u64) -> String {
+    let (hours, minutes, secs) = (seconds / 3_600, (seconds % 3_600) / 60, seconds % 60);
+    let mut out = String::new();
+    if hours > 0 {
+        out.push_str(&format!("{}h", hours));
+    }
+    if minutes > 0 {
+        out.push_str(&format!("{}m", minutes));
+    }
+    if secs > 0 || out.is_empty() {
+        out.push_str(&format!("{}s", secs));
+    }
+    out
+}
+
+/// An interval in milliseconds as `250ms`, `2s` or `5m`.
+pub fn format_millis(ms: u64) -> String {
+    if ms.is_multiple_of(1_000) {
+        format_span(ms / 1_000)
+    } else {
+        format!("{}ms", ms)
+    }
+}
+
+/// The data on screen while the display is paused.
+pub struct Paused {
+    pub snapshot: Arc<Snapshot>,
+    /// Unix time of the pause, where the charts stop.
+    pub at: u64,
+}
+
+pub struct App {
+    pub config: Config,
+    pub system_info: SystemInfo,
+    /// The container the monitor runs in, if any.
+    pub container: Option<ContainerLimits>,
+    /// The latest metrics published by the collection thread.
+    pub snapshot: Arc<Snapshot>,
+    /// Set while the display is frozen; collection, history and alerts carry on.
+    pub paused: Option<Paused>,
+    collection: Collection,
+    pub scheduler: Scheduler,
+    pub history: MetricsHistory,
+    pub chart: ChartView,
+    pub alerts: AlertEngine,
+    pub actions: ActionRunner,
+    /// Notifications waiting to be written to the terminal.
+    pub notifications: Vec<Notification>,
+    /// Whether the terminal window has focus, as reported by focus events.
+    pub focused: bool,
+    /// A short message shown in the footer, with when it was set.
+    pub status: Option<(Instant, String)>,
+    /// The panel keyboard actions apply to, highlighted with a primary border.
+    pub focus: Option<Panel>,
+    /// Whether the focused panel is zoomed to fill the screen.
+    pub zoomed: bool,
+    /// Rows scrolled past in each list panel.
+    pub scroll: HashMap<Panel, usize>,
+    pub process_sort: ProcessSort,
+    /// Chart range being selected with the mouse.
+    pub drag: Option<ChartDrag>,
+    /// Hit regions of the last frame, filled in while rendering.
+    pub regions: RefCell<Regions>,
+    pub keymap: Keymap,
+    pub show_help: bool,
+    config_watcher: ConfigWatcher,
+    /// Why the last edit to the config file couldn't be applied.
+    pub config_error: Option<String>,
+    pub should_quit: bool,
+    /// Whether the screen was switched, so it's written back on exit.
+    screen_changed: bool,
+}
+
+impl App {
+    pub fn new(config: Config, collection: Collection, container: Option<ContainerLimits>) -> Self {
+        let history = MetricsHistory::new(config.history_path());
+        let alerts = AlertEngine::new(&config.alerts);
+        let system_info = SystemInfo::new();
+        let actions = ActionRunner::new(config.dry_run_actions, system_info.hostname.clone());
+        let keymap = Keymap::new(&config.keys);
+        let scheduler = Scheduler::new(&config.intervals, config.refresh_rate);
+        let config_watcher = ConfigWatcher::new(config.path.as_deref());
+
+        Self {
+            config,
+            system_info,
+            container,
+            snapshot: Arc::default(),
+            paused: None,
+            collection,
+            scheduler,
+            history,
+            chart: ChartView::default(),
+            alerts,
+            actions,
+            notifications: Vec::new(),
+            focused: true,
+            status: None,
+            focus: None,
+            zoomed: false,
+            scroll: HashMap::new(),
+            process_sort: ProcessSort::default(),
+            drag: None,
+            regions: RefCell::default(),
+            keymap,
+            show_help: false,
+            config_watcher,
+            config_error: None,
+            should_quit: false,
+            screen_changed: false,
+        }
+    }
+
+    /// Asks the collection thread to sample whichever collectors are due.
+    pub fn update(&mut self) {
+        let due = self.scheduler.take_due(Instant::now());
+        self.collection.request(due);
+    }
+
+    /// The container CPU and memory are shown against, unless host values
+    /// were asked for or its cgroup can't be read.
+    pub fn container_view(&self) -> Option<&ContainerLimits> {
+        self.container
+            .as_ref()
+            .filter(|container| container.readable() && !self.config.host_values)
+    }
+
+    /// Samples every collector now, restarting their intervals.
+    pub fn refresh(&mut self) {
+        let all = self.scheduler.restart(Instant::now());
+        self.collection.request(all);
+    }
+
+    /// Applies edits to the config file, returning whether anything changed
+    /// on screen. A file that fails to load leaves the running config alone
+    /// and shows why until it's fixed.
+    pub fn reload_config(&mut self) -> bool {
+        if !self.config_watcher.changed() {
+            return false;
+        }
+
+        match self.config.reload() {
+            Ok((config, warnings)) => {
+                self.alerts.set_rules(&config.alerts);
+                self.keymap = Keymap::new(&config.keys);
+                self.scheduler.set_intervals(
+                    &config.intervals,
+                    config.refresh_rate,
+                    Instant::now(),
+                );
+                self.collection.set_host_values(config.host_values);
+                self.actions.set_dry_run(config.dry_run_actions);
+                self.config = config;
+                if self
+                    .focus
+                    .is_some_and(|panel| !self.visible_panels().contains(&panel))
+                {
+                    self.focus = None;
+                    self.zoomed = false;
+                }
+                self.config_error = None;
+                match warnings.first() {
+                    Some(warning) => self.set_status(format!("Config reloaded with {}", warning)),
+                    None => self.set_status("Config reloaded"),
+                }
+            }
+            Err(err) => self.config_error = Some(err.to_string()),
+        }
+        true
+    }
+
+    /// Takes in a snapshot published by the collection thread.
+    pub fn apply_snapshot(&mut self, snapshot: Arc<Snapshot>) {
+        let charted = [Collector::Cpu, Collector::Memory, Collector::Network]
+            .iter()
+            .any(|c| snapshot.updated.contains(c));
+        self.snapshot = snapshot;
+        // Passes that only sampled, say, disks would repeat the last chart values.
+        if charted {
+            self.record_history();
+        }
+        self.evaluate_alerts();
+    }
+
+    /// The snapshot to draw: the frozen one while paused, else the latest.
+    pub fn displayed(&self) -> &Snapshot {
+        match &self.paused {
+            Some(paused) => &paused.snapshot,
+            None => &self.snapshot,
+        }
+    }
+
+    /// Timestamp of the newest sample the charts should show.
+    pub fn chart_newest(&self, series: &TimeSeries) -> u64 {
+        let latest = series
+            .latest()
+            .map(|s| s.timestamp)
+            .unwrap_or_else(unix_now);
+        match &self.paused {
+            Some(paused) => latest.min(paused.at),
+            None => latest,
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = match self.paused {
+            Some(_) => None,
+            None => Some(Paused {
+                snapshot: Arc::clone(&self.snapshot),
+                at: unix_now(),
+            }),
+        };
+    }
+
+    /// Steps `refresh_rate` to the next faster or slower preset. Collectors
+    /// with their own interval in `[intervals]` keep it.
+    pub fn change_refresh_rate(&mut self, faster: bool) {
+        let current = self.config.refresh_rate;
+        let rate = if faster {
+            REFRESH_RATES.iter().rev().find(|&&rate| rate < current)
+        } else {
+            REFRESH_RATES.iter().find(|&&rate| rate > current)
+        };
+        let Some(&rate) = rate else {
+            return;
+        };
+        self.config.refresh_rate = rate;
+        self.scheduler
+            .set_intervals(&self.config.intervals, rate, Instant::now());
+        self.set_status(format!("Refresh rate {}", format_millis(rate)));
+    }
+
+    fn evaluate_alerts(&mut self) {
+        let snapshot = Arc::clone(&self.snapshot);
+        let metrics = Metrics {
+            cpu: snapshot.cpu.global_usage,
+            memory: snapshot.memory.used_percent(),
+            swap: snapshot.memory.swap_percent(),
+            disks: snapshot
+                .disks
+                .iter()
+                .map(|d| (d.mount_point.clone(), d.used_percent()))
+                .collect(),
+            net_rx: snapshot.network.rx_rate_mbps() as f32,
+            net_tx: snapshot.network.tx_rate_mbps() as f32,
+        };
+        let now = Instant::now();
+        for event in self.alerts.evaluate(&metrics, now) {
+            self.actions.dispatch(&event, now);
+
+            if event.kind == EventKind::Fired && !self.focused {
+                let body = match event.value {
+                    Some(value) => format!(
+                        "{} ({:.1}{})",
+                        event.rule.condition,
+                        value,
+                        event.rule.condition.metric.unit()
+                    ),
+                    None => event.rule.condition.to_string(),
+                };
+                for &notifier in &event.rule.notify {
+                    self.notifications.push(Notification {
+                        notifier,
+                        title: format!("{} on {}", event.rule.name, self.system_info.hostname),
+                        body: body.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn record_history(&mut self) {
+        let snapshot = &self.snapshot;
+        self.history.record(
+            snapshot.cpu.global_usage,
+            snapshot.memory.used_percent(),
+            snapshot.memory.swap_percent(),
+            snapshot.network.rx_rate,
+            snapshot.network.tx_rate,
+        );
+    }
+
+    pub fn save_history(&mut self) -> std::io::Result<()> {
+        self.history.save()
+    }
+
+    pub fn quit(&mut self) {
+        self.should_quit = true;
+    }
+
+    pub fn set_status(&mut self, message: impl Into<String>) {
+        self.status = Some((Instant::now(), message.into()));
+    }
+
+    pub fn set_screen(&mut self, screen: Screen) {
+        if self.config.screen == screen {
+            return;
+        }
+        self.config.screen = screen;
+        self.focus = None;
+        self.zoomed = false;
+        self.screen_changed = true;
+    }
+
+    /// Remembers the active screen in the config file, if it was switched.
+    pub fn save_screen(&mut self) -> anyhow::Result<()> {
+        if self.screen_changed {
+            self.config.persist_screen()?;
+        }
+        Ok(())
+    }
+
+    pub fn next_screen(&mut self) {
+        self.set_screen(self.config.screen.next());
+    }
+
+    pub fn previous_screen(&mut self) {
+        self.set_screen(self.config.screen.previous());
+    }
+
+    /// Panels shown on the current screen, in layout order.
+    pub fn visible_panels(&self) -> Vec<Panel> {
+        self.config
+            .screen_layout(self.config.screen)
+            .panels()
+            .into_iter()
+            .filter(|panel| self.config.panel_visible(*panel))
+            .collect()
+    }
+
+    pub fn zoomed_panel(&self) -> Option<Panel> {
+        self.focus.filter(|_| self.zoomed)
+    }
+
+    pub fn focus_next(&mut self) {
+        self.move_focus(1);
+    }
+
+    pub fn focus_previous(&mut self) {
+        self.move_focus(-1);
+    }
+
+    fn move_focus(&mut self, step: isize) {
+        let panels = self.visible_panels();
+        if panels.is_empty() {
+            return;
+        }
+        let len = panels.len() as isize;
+        let next = match self.focus.and_then(|f| panels.iter().position(|p| *p == f)) {
+            Some(idx) => (idx as isize + step).rem_euclid(len),
+            None if step > 0 => 0,
+            None => len - 1,
+        };
+        self.focus = Some(panels[next as usize]);
+    }
+
+    /// Zooms the focused panel, focusing the first one if nothing is focused yet.
+    pub fn toggle_zoom(&mut self) {
+        if self.focus.is_none() {
+            self.focus_next();
+        }
+        self.zoomed = self.focus.is_some() && !self.zoomed;
+    }
+
+    pub fn perform(&mut self, action: Action) {
+        match action {
+            Action::Quit => self.quit(),
+            Action::Back => {
+                if self.show_help {
+                    self.show_help = false;
+                } else if self.zoomed {
+                    self.zoomed = false;
+                } else {
+                    self.quit();
+                }
+            }
+            Action::Help => self.show_help = !self.show_help,
+            Action::Refresh => self.refresh(),
+            Action::Pause => self.toggle_pause(),
+            Action::RefreshFaster => self.change_refresh_rate(true),
+            Action::RefreshSlower => self.change_refresh_rate(false),
+            Action::SaveSettings => self.save_settings(),
+            Action::CycleTheme => self.cycle_theme(),
+            Action::NextScreen => self.next_screen(),
+            Action::PreviousScreen => self.previous_screen(),
+            Action::Screen(screen) => self.set_screen(screen),
+            Action::FocusNext => self.focus_next(),
+            Action::FocusPrevious => self.focus_previous(),
+            Action::Zoom => self.toggle_zoom(),
+            Action::ScrollUp => self.scroll_focused(-1),
+            Action::ScrollDown => self.scroll_focused(1),
+            Action::ScrollTop => self.scroll_focused(isize::MIN),
+            Action::ScrollBottom => self.scroll_focused(isize::MAX),
+            Action::ChartZoomIn => self.chart.zoom_in(),
+            Action::ChartZoomOut => self.chart.zoom_out(),
+            Action::ChartBack => self.scroll_chart_back(),
+            Action::ChartForward => self.chart.scroll_forward(),
+            Action::ChartLive => self.chart.go_live(),
+            Action::ToggleCursor => self.chart.toggle_cursor(),
+            Action::CursorLeft => self.chart.move_cursor(-1),
+            Action::CursorRight => self.chart.move_cursor(1),
+        }
+    }
+
+    /// Scrolls a list panel by `rows`, within what the last frame could show.
+    pub fn scroll_panel(&mut self, panel: Panel, rows: isize) {
+        let Some(limit) = self.regions.borrow().scroll_limits.get(&panel).copied() else {
+            return;
+        };
+        let offset = self.scroll.entry(panel).or_default();
+        *offset = offset.saturating_add_signed(rows).min(limit);
+    }
+
+    /// Scrolls the focused panel, or the first scrollable one on screen.
+    fn scroll_focused(&mut self, rows: isize) {
+        let panel = self.focus.or_else(|| {
+            let regions = self.regions.borrow();
+            regions
+                .panels
+                .iter()
+                .map(|(panel, _)| *panel)
+                .find(|panel| regions.scroll_limits.contains_key(panel))
+        });
+        if let Some(panel) = panel {
+            self.scroll_panel(panel, rows);
+        }
+    }
+
+    pub fn sort_processes_by(&mut self, column: ProcessColumn) {
+        self.process_sort = self.process_sort.by(column);
+        self.scroll.remove(&Panel::Processes);
+    }
+
+    pub fn scroll_chart_back(&mut self) {
+        let series = &self.history.cpu;
+        let max_offset = match series.oldest() {
+            Some(oldest) => self.chart_newest(series).saturating_sub(oldest.timestamp),
+            None => 0,
+        };
+        self.chart.scroll_back(max_offset);
+    }
+
+    /// Writes the theme, refresh rate and other runtime changes to the config file.
+    pub fn save_settings(&mut self) {
+        match self.config.save() {
+            Ok(()) => {
+                let path = self
+                    .config
+                    .path
+                    .as_deref()
+                    .unwrap_or(std::path::Path::new(""));
+                self.set_status(format!("Settings saved to {}", path.display()));
+            }
+            Err(err) => self.set_status(format!("Could not save settings: {}", err)),
+        }
+        self.config_watcher.mark_seen();
+    }
+
+    /// Switches to the next theme in the registry; a `[theme]` table of the
+    /// config's own is followed by the first one.
+    pub fn cycle_theme(&mut self) {
+        let themes = &self.config.themes;
+        let name = themes
+            .next_after(self.config.theme_choice.label())
+            .to_string();
+        if let Some(theme) = themes.get(&name) {
+            self.config.theme = theme;
+            self.config.theme_choice = ThemeChoice::Name(name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CliArgs;
+    use std::fs;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn reload_applies_dry_run_actions() {
+        let dir =
+            std::env::temp_dir().join(format!("rust-tui-monitor-reload-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("themes")).unwrap();
+        let path = dir.join("config.toml");
+        fs::write(&path, "persist_history = false\n").unwrap();
+
+        let (config, _) =
+            Config::read(path.clone(), Some(dir.join("themes")), CliArgs::default()).unwrap();
+        let (collection, _snapshots) = Collection::spawn(None, false);
+        let mut app = App::new(config, collection, None);
+        assert!(!app.actions.is_dry_run());
+
+        fs::write(&path, "persist_history = false\ndry_run_actions = true\n").unwrap();
+        // Make sure the edit is seen even where timestamps are coarse.
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+        assert!(app.reload_config());
+        assert!(app.actions.is_dry_run());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,107 +1,949 @@
-use crate::theme::Theme;
-use anyhow::Result;
-use clap::Parser;
-use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
-
-#[derive(Parser, Debug)]
-#[command(name = "rust-tui-monitor")]
-#[command(about = "A lightweight TUI system monitor", long_about = None)]
-pub struct CliArgs {
-    #[arg(short, long, help = "Theme name (default, nord, gruvbox, dracula, monokai)")]
-    pub theme: Option<String>,
-
-    #[arg(short, long, help = "Path to custom config file")]
-    pub config: Option<PathBuf>,
-
-    #[arg(short, long, help = "Refresh rate in milliseconds")]
-    pub refresh_rate: Option<u64>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Config {
-    pub theme: Theme,
-    pub refresh_rate: u64,
-    pub show_cpu: bool,
-    pub show_memory: bool,
-    pub show_disk: bool,
-    pub show_network: bool,
-    pub rounded_borders: bool,
-    pub temp_unit: String,
-}
-
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            theme: Theme::default(),
-            refresh_rate: 1000,
-            show_cpu: true,
-            show_memory: true,
-            show_disk: true,
-            show_network: true,
-            rounded_borders: true,
-            temp_unit: "celsius".to_string(),
-        }
-    }
-}
-
-impl Config {
-    pub fn load() -> Result<Self> {
-        let args = CliArgs::parse();
-        let mut config = Self::load_from_file(args.config.as_deref())?;
-
-        if let Some(theme_name) = args.theme {
-            config.theme = Theme::from_name(&theme_name);
-        }
-
-        if let Some(rate) = args.refresh_rate {
-            config.refresh_rate = rate;
-        }
-
-        Ok(config)
-    }
-
-    fn load_from_file(path: Option<&std::path::Path>) -> Result<Self> {
-        let config_path = path
-            .map(|p| p.to_path_buf())
-            .or_else(|| {
-                dirs::config_dir().map(|mut p| {
-                    p.push("rust-tui-monitor");
-                    p.push("config.toml");
-                    p
-                })
-            })
-            .unwrap_or_else(|| PathBuf::from("config.toml"));
-
-        if config_path.exists() {
-            let content = fs::read_to_string(&config_path)?;
-            let config: Config = toml::from_str(&content)?;
-            Ok(config)
-        } else {
-            Ok(Self::default())
-        }
-    }
-}
-
-fn dirs() -> Option<PathBuf> {
-    if cfg!(target_os = "windows") {
-        std::env::var("APPDATA").ok().map(PathBuf::from)
-    } else if cfg!(target_os = "macos") {
-        std::env::var("HOME")
-            .ok()
-            .map(|h| PathBuf::from(h).join("Library/Application Support"))
-    } else {
-        std::env::var("HOME")
-            .ok()
-            .map(|h| PathBuf::from(h).join(".config"))
-    }
-}
-
-mod dirs {
-    use std::path::PathBuf;
-    pub fn config_dir() -> Option<PathBuf> {
-        super::dirs()
-    }
-}
-
+use crate::alerts::AlertRule;
+use crate::keys::KeysConfig;
+use crate::layout::{LayoutConfig, Panel, Screen};
+use crate::system::scheduler::MIN_INTERVAL;
+use crate::system::{Collector, IntervalsConfig};
+use crate::theme::{ColorDepth, Theme, ThemeChoice, ThemeRegistry};
+use anyhow::{anyhow, bail, Result};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+#[derive(Parser, Debug, Clone, Default)]
+#[command(name = "rust-tui-monitor")]
+#[command(about = "A lightweight TUI system monitor", long_about = None)]
+pub struct CliArgs {
+    #[arg(
+        short,
+        long,
+        help = "Theme name: a built-in theme or a file in the themes directory"
+    )]
+    pub theme: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "DEPTH",
+        help = "Colors the terminal can show, instead of detecting them"
+    )]
+    pub colors: Option<ColorDepth>,
+
+    #[arg(short, long, help = "Path to custom config file")]
+    pub config: Option<PathBuf>,
+
+    #[arg(short, long, help = "Refresh rate in milliseconds")]
+    pub refresh_rate: Option<u64>,
+
+    #[arg(long, help = "Don't load or save the on-disk metrics history")]
+    pub no_history: bool,
+
+    #[arg(long, help = "Log alert actions instead of running them")]
+    pub dry_run_actions: bool,
+
+    #[arg(
+        long,
+        help = "Inside a container, show host-wide CPU and memory instead of the container's"
+    )]
+    pub host_values: bool,
+
+    #[arg(long, help = "Check the config file for problems and exit")]
+    pub check_config: bool,
+
+    #[arg(
+        long,
+        help = "Print a commented config file with every default and exit"
+    )]
+    pub print_default_config: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Print a theme made from an Alacritty, Kitty, WezTerm or base16 color scheme and exit"
+    )]
+    pub import_theme: Option<PathBuf>,
+}
+
+/// Every key is optional; missing ones take the values in `Config::default`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// The theme as configured: a name, or a table of colors.
+    #[serde(rename = "theme")]
+    pub theme_choice: ThemeChoice,
+    /// The colors `theme_choice` resolves to.
+    #[serde(skip)]
+    pub theme: Theme,
+    /// Built-in themes plus the user's theme files.
+    #[serde(skip)]
+    pub themes: ThemeRegistry,
+    pub refresh_rate: u64,
+    /// Sample intervals of individual collectors, overriding `refresh_rate`.
+    #[serde(skip_serializing_if = "IntervalsConfig::is_empty")]
+    pub intervals: IntervalsConfig,
+    pub show_cpu: bool,
+    pub show_memory: bool,
+    pub show_disk: bool,
+    pub show_network: bool,
+    pub rounded_borders: bool,
+    pub temp_unit: String,
+    pub persist_history: bool,
+    pub dry_run_actions: bool,
+    /// Inside a container, report host-wide CPU and memory rather than usage
+    /// against the container's limits.
+    pub host_values: bool,
+    /// The active screen, remembered across restarts.
+    pub screen: Screen,
+    pub alerts: Vec<AlertRule>,
+    /// Layout of the overview screen.
+    pub layout: LayoutConfig,
+    /// Layouts of the other screens, overriding their built-in defaults.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub screens: BTreeMap<Screen, LayoutConfig>,
+    /// Key bindings replacing or adding to the defaults.
+    #[serde(skip_serializing_if = "KeysConfig::is_empty")]
+    pub keys: KeysConfig,
+    /// Where the config was loaded from, or would be created.
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+    /// Where user themes were loaded from, for reloads to look there again.
+    #[serde(skip)]
+    themes_dir: Option<PathBuf>,
+    /// Command-line overrides, reapplied when the file is reloaded.
+    #[serde(skip)]
+    cli: CliArgs,
+    /// The file's values of the settings command-line flags can override.
+    #[serde(skip)]
+    file: FileValues,
+}
+
+/// Settings as the config file had them, before command-line overrides, so
+/// saving doesn't persist a flag and reloading can tell what the file changed.
+#[derive(Debug, Clone, Default)]
+struct FileValues {
+    theme_choice: ThemeChoice,
+    refresh_rate: u64,
+    persist_history: bool,
+    dry_run_actions: bool,
+    host_values: bool,
+}
+
+/// Comments placed above each key by `Config::default_template`.
+const TEMPLATE_COMMENTS: [(&str, &str); 10] = [
+    ("refresh_rate", "Milliseconds between samples. Collectors can override it under [intervals]."),
+    ("show_cpu", "Panels shown on the overview screen."),
+    ("rounded_borders", "Draw panel borders with rounded corners."),
+    ("temp_unit", "\"celsius\", \"fahrenheit\" or \"kelvin\"."),
+    ("persist_history", "Keep the metrics history on disk across restarts."),
+    ("dry_run_actions", "Log alert actions instead of running them."),
+    ("host_values", "Inside a container, show host-wide CPU and memory instead of usage\nagainst the container's limits."),
+    ("screen", "Screen shown at startup; switching screens updates it.\nOne of overview, processes, network, disks, sensors, alerts, cgroups, services."),
+    ("theme", "A built-in theme or the name of a file in ~/.config/rust-tui-monitor/themes/.\nIt can also be a [theme] table of colors as [red, green, blue], with\nextends = \"nord\" to start from another theme instead of the default."),
+    ("layout", "Overview layout: rows of columns of panels. Sizes are relative weights."),
+];
+
+/// Optional sections `Config::default_template` shows commented out.
+const TEMPLATE_EXAMPLES: &str = r#"
+# Sample intervals of single collectors in milliseconds, overriding
+# refresh_rate: cpu, memory, disks, network, processes, sensors, cgroups,
+# services.
+# [intervals]
+# cpu = 250
+# disks = 30000
+
+# Layouts of the other screens, in the same form as [layout].
+# [screens.processes]
+# rows = [{ columns = [["processes"]] }]
+
+# Key bindings, replacing the defaults of the actions named.
+# Press ? in the monitor for every action.
+# [keys]
+# preset = "vim"
+# quit = ["q", "ctrl+c"]
+
+# Alert rules, one [[alerts]] table each.
+# [[alerts]]
+# name = "High CPU"
+# condition = "cpu > 90 for 30s"
+# notify = ["bell"]
+"#;
+
+/// Values `temp_unit` accepts, compared case-insensitively.
+const TEMP_UNITS: [&str; 6] = ["celsius", "fahrenheit", "kelvin", "c", "f", "k"];
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            theme_choice: ThemeChoice::default(),
+            theme: Theme::default(),
+            themes: ThemeRegistry::default(),
+            refresh_rate: 1000,
+            intervals: IntervalsConfig::default(),
+            show_cpu: true,
+            show_memory: true,
+            show_disk: true,
+            show_network: true,
+            rounded_borders: true,
+            temp_unit: "celsius".to_string(),
+            persist_history: true,
+            dry_run_actions: false,
+            host_values: false,
+            screen: Screen::default(),
+            alerts: Vec::new(),
+            layout: LayoutConfig::default(),
+            screens: BTreeMap::new(),
+            keys: KeysConfig::default(),
+            path: None,
+            themes_dir: None,
+            cli: CliArgs::default(),
+            file: FileValues::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config file named on the command line, or the default one,
+    /// with the command-line overrides applied. Problems that would make it
+    /// misbehave fail the load; the warnings left over are returned.
+    pub fn load(args: CliArgs) -> Result<(Self, Vec<Diagnostic>)> {
+        let path = config_path(args.config.as_deref());
+        let (config, diagnostics) = Self::read(path, themes_dir(), args)?;
+        let warnings = config.reject_errors(diagnostics)?;
+        Ok((config, warnings))
+    }
+
+    /// Reads the config file again, keeping the command-line overrides, the
+    /// active screen, and the theme and refresh rate picked at runtime unless
+    /// the edit changed those keys. Parse errors point at the offending line
+    /// and column.
+    pub fn reload(&self) -> Result<(Self, Vec<Diagnostic>)> {
+        let Some(path) = self.path.clone() else {
+            return Ok((self.clone(), Vec::new()));
+        };
+
+        let (mut config, diagnostics) =
+            Self::read(path, self.themes_dir.clone(), self.cli.clone())?;
+        config.screen = self.screen;
+        if config.file.refresh_rate == self.file.refresh_rate {
+            config.refresh_rate = self.refresh_rate;
+        }
+        if config.file.theme_choice == self.file.theme_choice {
+            if let Ok(theme) = config.themes.resolve(&self.theme_choice) {
+                config.theme = theme;
+                config.theme_choice = self.theme_choice.clone();
+            }
+        }
+        let warnings = config.reject_errors(diagnostics)?;
+        Ok((config, warnings))
+    }
+
+    /// Prints every problem with the config file, for `--check-config`.
+    /// Returns whether the file would load.
+    pub fn check_file(args: CliArgs) -> bool {
+        let path = config_path(args.config.as_deref());
+        println!("Checking {}", path.display());
+        if !path.exists() {
+            println!("The file doesn't exist, so the built-in defaults are used.");
+        }
+
+        let diagnostics = match Self::read(path, themes_dir(), args) {
+            Ok((_, diagnostics)) => diagnostics,
+            Err(err) => {
+                println!("error: {}", err);
+                return false;
+            }
+        };
+        for diagnostic in &diagnostics {
+            println!("{}", diagnostic);
+        }
+
+        let errors = diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .count();
+        let warnings = diagnostics.len() - errors;
+        if diagnostics.is_empty() {
+            println!("No problems found.");
+        } else {
+            println!("{} error(s), {} warning(s)", errors, warnings);
+        }
+        errors == 0
+    }
+
+    /// Reads `path`, looking for user themes in `themes_dir`.
+    pub(crate) fn read(
+        path: PathBuf,
+        themes_dir: Option<PathBuf>,
+        args: CliArgs,
+    ) -> Result<(Self, Vec<Diagnostic>)> {
+        let (mut config, mut diagnostics) = if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            parse(&content).map_err(|err| anyhow!("{}: {}", file_name(&path), err))?
+        } else {
+            (Self::default(), Vec::new())
+        };
+        config.path = Some(path);
+        config.apply_args(args);
+        diagnostics.extend(config.check());
+
+        let depth = config.cli.colors.unwrap_or_else(ColorDepth::detect);
+        let (themes, problems) = ThemeRegistry::load(themes_dir.as_deref(), depth);
+        diagnostics.extend(problems.into_iter().map(Diagnostic::warning));
+        match themes.resolve(&config.theme_choice) {
+            Ok(theme) => {
+                if let Some(problem) = theme.thresholds.problem() {
+                    diagnostics.push(Diagnostic::error(format!("theme: {}", problem)));
+                }
+                config.theme = theme;
+            }
+            Err(message) => diagnostics.push(Diagnostic::error(message)),
+        }
+        config.themes = themes;
+        config.themes_dir = themes_dir;
+        Ok((config, diagnostics))
+    }
+
+    fn apply_args(&mut self, args: CliArgs) {
+        self.file = FileValues {
+            theme_choice: self.theme_choice.clone(),
+            refresh_rate: self.refresh_rate,
+            persist_history: self.persist_history,
+            dry_run_actions: self.dry_run_actions,
+            host_values: self.host_values,
+        };
+
+        if let Some(theme_name) = &args.theme {
+            self.theme_choice = ThemeChoice::Name(theme_name.clone());
+        }
+
+        if let Some(rate) = args.refresh_rate {
+            self.refresh_rate = rate;
+        }
+
+        if args.no_history {
+            self.persist_history = false;
+        }
+
+        if args.dry_run_actions {
+            self.dry_run_actions = true;
+        }
+
+        if args.host_values {
+            self.host_values = true;
+        }
+
+        self.cli = args;
+    }
+
+    /// Values that parse but can't be used as they are.
+    fn check(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        if self.refresh_rate == 0 {
+            diagnostics.push(Diagnostic::error("refresh_rate must be greater than 0"));
+        }
+        for collector in Collector::ALL {
+            match self.intervals.get(collector) {
+                Some(0) => diagnostics.push(Diagnostic::error(format!(
+                    "intervals.{} must be greater than 0",
+                    collector.name()
+                ))),
+                Some(ms) if ms < MIN_INTERVAL => diagnostics.push(Diagnostic::warning(format!(
+                    "intervals.{} is below the {}ms minimum and will be raised to it",
+                    collector.name(),
+                    MIN_INTERVAL
+                ))),
+                _ => {}
+            }
+        }
+
+        if !TEMP_UNITS.contains(&self.temp_unit.to_lowercase().as_str()) {
+            diagnostics.push(Diagnostic::error(format!(
+                "temp_unit must be one of {}, not `{}`",
+                TEMP_UNITS.join(", "),
+                self.temp_unit
+            )));
+        }
+
+        let layouts = std::iter::once(("layout".to_string(), &self.layout)).chain(
+            self.screens
+                .iter()
+                .map(|(screen, layout)| (format!("screens.{}", screen.name()), layout)),
+        );
+        for (key, layout) in layouts {
+            if layout.rows.is_empty() {
+                diagnostics.push(Diagnostic::error(format!("{} has no rows", key)));
+            } else if layout.has_zero_size() {
+                diagnostics.push(Diagnostic::error(format!(
+                    "{} sizes must be greater than 0",
+                    key
+                )));
+            }
+        }
+
+        for name in self.keys.unknown_actions() {
+            diagnostics.push(Diagnostic::warning(format!(
+                "keys: unknown action `{}` is ignored",
+                name
+            )));
+        }
+
+        let mut names = BTreeSet::new();
+        for rule in &self.alerts {
+            if !names.insert(rule.name.as_str()) {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "alert `{}` is defined more than once",
+                    rule.name
+                )));
+            }
+            for action in &rule.actions {
+                if let Some(interval) = action.invalid_min_interval() {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "alert `{}`: invalid min_interval `{}`, expected a duration like 30s, 5m or 1h",
+                        rule.name, interval
+                    )));
+                }
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Fails with every error found, returning the warnings otherwise.
+    fn reject_errors(&self, diagnostics: Vec<Diagnostic>) -> Result<Vec<Diagnostic>> {
+        let (errors, warnings): (Vec<_>, Vec<_>) = diagnostics
+            .into_iter()
+            .partition(|d| d.severity == Severity::Error);
+        if errors.is_empty() {
+            return Ok(warnings);
+        }
+
+        let name = self.path.as_deref().map(file_name).unwrap_or_default();
+        let messages: Vec<_> = errors.iter().map(|e| e.message.as_str()).collect();
+        bail!("{}: {}", name, messages.join("; "))
+    }
+
+    pub fn panel_visible(&self, panel: Panel) -> bool {
+        match panel {
+            Panel::Cpu => self.show_cpu,
+            Panel::Memory => self.show_memory,
+            Panel::Disks => self.show_disk,
+            Panel::Network => self.show_network,
+            _ => true,
+        }
+    }
+
+    /// Formats a Celsius reading in the configured `temp_unit`.
+    pub fn format_temp(&self, celsius: f32) -> String {
+        match self.temp_unit.to_lowercase().as_str() {
+            "fahrenheit" | "f" => format!("{:.1}°F", celsius * 9.0 / 5.0 + 32.0),
+            "kelvin" | "k" => format!("{:.1}K", celsius + 273.15),
+            _ => format!("{:.1}°C", celsius),
+        }
+    }
+
+    pub fn history_path(&self) -> Option<PathBuf> {
+        if !self.persist_history {
+            return None;
+        }
+
+        dirs::data_dir().map(|mut p| {
+            p.push("rust-tui-monitor");
+            p.push("history.bin");
+            p
+        })
+    }
+
+    pub fn screen_layout(&self, screen: Screen) -> LayoutConfig {
+        match screen {
+            Screen::Overview => self.layout.clone(),
+            _ => self
+                .screens
+                .get(&screen)
+                .cloned()
+                .unwrap_or_else(|| screen.default_layout()),
+        }
+    }
+
+    /// Stores the active screen in the config file, editing just that key so
+    /// the rest of it is left untouched. Without a file nothing is written;
+    /// the screen is then only remembered once settings are saved.
+    pub fn persist_screen(&self) -> Result<()> {
+        let Some(path) = self.path.as_deref().filter(|path| path.exists()) else {
+            return Ok(());
+        };
+
+        let content = fs::read_to_string(path)?;
+        let mut doc: toml_edit::DocumentMut = content.parse()?;
+        if doc.get("screen").and_then(|item| item.as_str()) == Some(self.screen.name()) {
+            return Ok(());
+        }
+        doc["screen"] = toml_edit::value(self.screen.name());
+        fs::write(path, doc.to_string())?;
+        Ok(())
+    }
+
+    /// Writes the current settings to the config file. An existing file is
+    /// edited in place, keeping its comments, formatting and any keys this
+    /// version doesn't know. Session-only command-line flags aren't saved.
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            bail!("no config file location");
+        };
+
+        let mut settings: toml_edit::DocumentMut =
+            toml::to_string(&self.without_overrides())?.parse()?;
+
+        let mut doc = if path.exists() {
+            fs::read_to_string(path)?.parse()?
+        } else {
+            toml_edit::DocumentMut::new()
+        };
+
+        // Keep a partial config partial: defaults are only written for keys
+        // the file already has.
+        let defaults: toml_edit::DocumentMut = toml::to_string(&Self::default())?.parse()?;
+        let unchanged: Vec<String> = settings
+            .iter()
+            .filter(|(key, item)| {
+                !doc.contains_key(key)
+                    && defaults.get(key).map(|d| d.to_string()) == Some(item.to_string())
+            })
+            .map(|(key, _)| key.to_string())
+            .collect();
+        for key in unchanged {
+            settings.remove(&key);
+        }
+        merge_table(doc.as_table_mut(), settings.as_table());
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, doc.to_string())?;
+        Ok(())
+    }
+
+    /// These settings with the file's values back in place of command-line
+    /// overrides still in effect. A setting changed since, like a theme
+    /// picked with `t`, is kept.
+    fn without_overrides(&self) -> Self {
+        let mut config = self.clone();
+        let cli = &self.cli;
+        if matches!(&config.theme_choice, ThemeChoice::Name(name) if Some(name) == cli.theme.as_ref())
+        {
+            config.theme_choice = self.file.theme_choice.clone();
+        }
+        if cli.refresh_rate == Some(config.refresh_rate) {
+            config.refresh_rate = self.file.refresh_rate;
+        }
+        if cli.no_history && !config.persist_history {
+            config.persist_history = self.file.persist_history;
+        }
+        if cli.dry_run_actions && config.dry_run_actions {
+            config.dry_run_actions = self.file.dry_run_actions;
+        }
+        if cli.host_values && config.host_values {
+            config.host_values = self.file.host_values;
+        }
+        config
+    }
+
+    /// Every setting at its default, with comments explaining each, for
+    /// `--print-default-config`.
+    pub fn default_template() -> Result<String> {
+        let mut doc: toml_edit::DocumentMut = toml::to_string(&Self::default())?.parse()?;
+        // Shown as a commented example instead, so uncommenting it doesn't clash.
+        doc.remove("alerts");
+        for (key, comment) in TEMPLATE_COMMENTS {
+            let prefix = format!("\n# {}\n", comment.replace('\n', "\n# "));
+            match doc.get_mut(key) {
+                // `[layout]` is only implied by its `[[layout.rows]]`, so the
+                // comment goes on the first of those.
+                Some(toml_edit::Item::Table(table)) if table.is_implicit() => {
+                    let first = table
+                        .iter_mut()
+                        .find_map(|(_, item)| item.as_array_of_tables_mut()?.get_mut(0));
+                    if let Some(first) = first {
+                        first.decor_mut().set_prefix(prefix);
+                    }
+                }
+                Some(toml_edit::Item::Table(table)) => table.decor_mut().set_prefix(prefix),
+                Some(_) => {
+                    if let Some(mut key) = doc.key_mut(key) {
+                        key.leaf_decor_mut().set_prefix(prefix);
+                    }
+                }
+                None => {}
+            }
+        }
+
+        Ok(format!(
+            "# rust-tui-monitor configuration. Every key is optional.\n{}{}",
+            doc.to_string().trim_start(),
+            TEMPLATE_EXAMPLES
+        ))
+    }
+}
+
+fn dirs() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        std::env::var("APPDATA").ok().map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        std::env::var("HOME")
+            .ok()
+            .map(|h| PathBuf::from(h).join("Library/Application Support"))
+    } else {
+        std::env::var("HOME")
+            .ok()
+            .map(|h| PathBuf::from(h).join(".config"))
+    }
+}
+
+mod dirs {
+    use std::path::PathBuf;
+    pub fn config_dir() -> Option<PathBuf> {
+        super::dirs()
+    }
+
+    pub fn data_dir() -> Option<PathBuf> {
+        if cfg!(target_os = "windows") {
+            std::env::var("LOCALAPPDATA").ok().map(PathBuf::from)
+        } else if cfg!(target_os = "macos") {
+            super::dirs()
+        } else {
+            std::env::var("XDG_DATA_HOME")
+                .ok()
+                .filter(|d| !d.is_empty())
+                .map(PathBuf::from)
+                .or_else(|| {
+                    std::env::var("HOME")
+                        .ok()
+                        .map(|h| PathBuf::from(h).join(".local/share"))
+                })
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A problem found in the config file.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+        }
+    }
+
+    fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "warning: {}", self.message),
+            Severity::Error => write!(f, "error: {}", self.message),
+        }
+    }
+}
+
+/// Parses a config file, warning about keys it doesn't know rather than
+/// failing on them.
+fn parse(content: &str) -> Result<(Config, Vec<Diagnostic>), String> {
+    let mut unknown = Vec::new();
+    let config: Config = serde_ignored::deserialize(toml::Deserializer::new(content), |path| {
+        unknown.push(path.to_string())
+    })
+    .map_err(|err| describe_toml_error(content, &err))?;
+
+    let document = toml_edit::ImDocument::parse(content).ok();
+    let diagnostics = unknown
+        .into_iter()
+        .map(|path| {
+            let segments: Vec<&str> = path.split('.').collect();
+            let location = document
+                .as_ref()
+                .and_then(|doc| find_key(doc.as_table(), &segments))
+                .and_then(|key| key.span())
+                .map(|span| {
+                    let (line, column) = line_column(content, span.start);
+                    format!("line {}, column {}: ", line, column)
+                })
+                .unwrap_or_default();
+            Diagnostic::warning(format!("{}unknown key `{}` is ignored", location, path))
+        })
+        .collect();
+
+    Ok((config, diagnostics))
+}
+
+/// The key at a dotted path such as `alerts.0.name`, for its position.
+fn find_key<'a>(
+    table: &'a dyn toml_edit::TableLike,
+    segments: &[&str],
+) -> Option<&'a toml_edit::Key> {
+    let (first, rest) = segments.split_first()?;
+    let (key, item) = table.get_key_value(first)?;
+    let Some((next, after)) = rest.split_first() else {
+        return Some(key);
+    };
+
+    match next.parse::<usize>() {
+        Ok(index) => {
+            let element: &dyn toml_edit::TableLike = match item {
+                toml_edit::Item::ArrayOfTables(tables) => tables.get(index)?,
+                toml_edit::Item::Value(toml_edit::Value::Array(array)) => {
+                    array.get(index)?.as_inline_table()?
+                }
+                _ => return None,
+            };
+            find_key(element, after)
+        }
+        Err(_) => find_key(item.as_table_like()?, rest),
+    }
+}
+
+/// One-based line and column of a byte offset.
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.chars().rev().take_while(|&c| c != '\n').count() + 1;
+    (line, column)
+}
+
+/// A TOML error on one line, as `line 3, column 14: expected a boolean`.
+fn describe_toml_error(content: &str, err: &toml::de::Error) -> String {
+    let message = err.message().trim().replace('\n', "; ");
+    match err.span() {
+        Some(span) => {
+            let (line, column) = line_column(content, span.start);
+            format!("line {}, column {}: {}", line, column, message)
+        }
+        None => message,
+    }
+}
+
+/// Where user theme files are looked for.
+fn themes_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|p| p.join("rust-tui-monitor").join("themes"))
+}
+
+fn config_path(path: Option<&Path>) -> PathBuf {
+    path.map(Path::to_path_buf)
+        .or_else(|| {
+            dirs::config_dir().map(|mut p| {
+                p.push("rust-tui-monitor");
+                p.push("config.toml");
+                p
+            })
+        })
+        .unwrap_or_else(|| PathBuf::from("config.toml"))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Notices edits to the config file by polling its modification time.
+#[derive(Debug)]
+pub struct ConfigWatcher {
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: Option<&Path>) -> Self {
+        let path = path.map(Path::to_path_buf);
+        let modified = path.as_deref().and_then(modified_time);
+        Self { path, modified }
+    }
+
+    /// Whether the file was changed, created or removed since the last check.
+    pub fn changed(&mut self) -> bool {
+        let modified = self.path.as_deref().and_then(modified_time);
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        true
+    }
+
+    /// Takes the file as it is now as seen, after writing it ourselves.
+    pub fn mark_seen(&mut self) {
+        self.changed();
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Copies `new` into `old`, keeping `old`'s comments and formatting and the
+/// keys only it has.
+fn merge_table(old: &mut dyn toml_edit::TableLike, new: &dyn toml_edit::TableLike) {
+    for (key, item) in new.iter() {
+        match old.get_mut(key) {
+            // A value replacing a table or the other way around takes the new
+            // key's formatting too; the old one was written for the other form.
+            Some(existing) if existing.is_table_like() != item.is_table_like() => {
+                old.remove(key);
+                old.insert(key, item.clone());
+            }
+            Some(existing) => merge_item(existing, item),
+            None => {
+                old.insert(key, item.clone());
+            }
+        }
+    }
+}
+
+fn merge_item(old: &mut toml_edit::Item, new: &toml_edit::Item) {
+    use toml_edit::Item;
+
+    if old.is_table_like() && new.is_table_like() {
+        if let (Some(old), Some(new)) = (old.as_table_like_mut(), new.as_table_like()) {
+            merge_table(old, new);
+        }
+        return;
+    }
+
+    match (old, new) {
+        (Item::ArrayOfTables(old), Item::ArrayOfTables(new)) if old.len() == new.len() => {
+            for (old, new) in old.iter_mut().zip(new.iter()) {
+                merge_table(old, new);
+            }
+        }
+        (Item::Value(old), Item::Value(new)) => {
+            if !same_value(old, new) {
+                let decor = old.decor().clone();
+                *old = new.clone();
+                *old.decor_mut() = decor;
+            }
+        }
+        (old, new) => *old = new.clone(),
+    }
+}
+
+/// Whether two values are written the same, ignoring surrounding whitespace
+/// and comments.
+fn same_value(a: &toml_edit::Value, b: &toml_edit::Value) -> bool {
+    let (mut a, mut b) = (a.clone(), b.clone());
+    a.decor_mut().clear();
+    b.decor_mut().clear();
+    a.to_string() == b.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "\
+# Sampled every second.
+refresh_rate = 1000 # ms
+temp_unit = \"fahrenheit\"
+future_option = true
+";
+
+    /// A directory of its own for each test, holding its config file and an
+    /// empty themes directory, so the user's own themes aren't read.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("rust-tui-monitor-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("themes")).unwrap();
+        dir
+    }
+
+    fn load(dir: &Path, args: CliArgs) -> Config {
+        Config::read(dir.join("config.toml"), Some(dir.join("themes")), args)
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn save_leaves_command_line_overrides_out() {
+        let dir = temp_dir("save-overrides");
+        let path = dir.join("config.toml");
+        fs::write(&path, FILE).unwrap();
+
+        let args = CliArgs {
+            refresh_rate: Some(250),
+            theme: Some("gruvbox".to_string()),
+            ..CliArgs::default()
+        };
+        let config = load(&dir, args);
+        assert_eq!(config.refresh_rate, 250);
+        config.save().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), FILE);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn save_merges_runtime_changes_into_the_file() {
+        let dir = temp_dir("save-merge");
+        let path = dir.join("config.toml");
+        fs::write(&path, FILE).unwrap();
+
+        let mut config = load(&dir, CliArgs::default());
+        config.refresh_rate = 2000;
+        config.save().unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            FILE.replace("1000", "2000")
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn save_writes_only_changed_keys_to_a_new_file() {
+        let dir = temp_dir("save-new");
+        let path = dir.join("config.toml");
+
+        let mut config = load(&dir, CliArgs::default());
+        config.refresh_rate = 2000;
+        config.save().unwrap();
+        let saved: toml::Table = fs::read_to_string(&path).unwrap().parse().unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved["refresh_rate"].as_integer(), Some(2000));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn save_refuses_to_overwrite_a_broken_file() {
+        let dir = temp_dir("save-broken");
+        let path = dir.join("config.toml");
+        fs::write(&path, FILE).unwrap();
+        let mut config = load(&dir, CliArgs::default());
+
+        let broken = "refresh_rate = [\n";
+        fs::write(&path, broken).unwrap();
+        config.refresh_rate = 2000;
+        assert!(config.save().is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), broken);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,567 @@
+use crate::layout::Screen;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Something a key can be bound to in the `[keys]` config section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Action {
+    Quit,
+    /// Close the help overlay, leave zoom, or quit, whichever applies first.
+    Back,
+    Help,
+    Refresh,
+    /// Freeze or unfreeze the displayed data.
+    Pause,
+    RefreshFaster,
+    RefreshSlower,
+    /// Write the current settings back to the config file.
+    SaveSettings,
+    CycleTheme,
+    NextScreen,
+    PreviousScreen,
+    Screen(Screen),
+    FocusNext,
+    FocusPrevious,
+    Zoom,
+    ScrollUp,
+    ScrollDown,
+    ScrollTop,
+    ScrollBottom,
+    ChartZoomIn,
+    ChartZoomOut,
+    ChartBack,
+    ChartForward,
+    ChartLive,
+    ToggleCursor,
+    CursorLeft,
+    CursorRight,
+}
+
+impl Action {
+    /// Every action, in the order the help overlay lists them.
+    pub fn all() -> Vec<Action> {
+        use Action::*;
+        let mut actions = vec![
+            Quit,
+            Back,
+            Help,
+            Refresh,
+            Pause,
+            RefreshFaster,
+            RefreshSlower,
+            SaveSettings,
+            CycleTheme,
+            NextScreen,
+            PreviousScreen,
+        ];
+        actions.extend(
+            crate::layout::Screen::ALL
+                .iter()
+                .map(|s| Action::Screen(*s)),
+        );
+        actions.extend([
+            FocusNext,
+            FocusPrevious,
+            Zoom,
+            ScrollUp,
+            ScrollDown,
+            ScrollTop,
+            ScrollBottom,
+            ChartZoomIn,
+            ChartZoomOut,
+            ChartBack,
+            ChartForward,
+            ChartLive,
+            ToggleCursor,
+            CursorLeft,
+            CursorRight,
+        ]);
+        actions
+    }
+
+    /// The name used in the config file.
+    pub fn name(self) -> String {
+        let name = match self {
+            Action::Quit => "quit",
+            Action::Back => "back",
+            Action::Help => "help",
+            Action::Refresh => "refresh",
+            Action::Pause => "pause",
+            Action::RefreshFaster => "refresh_faster",
+            Action::RefreshSlower => "refresh_slower",
+            Action::SaveSettings => "save_settings",
+            Action::CycleTheme => "theme",
+            Action::NextScreen => "next_screen",
+            Action::PreviousScreen => "previous_screen",
+            Action::Screen(screen) => return format!("screen_{}", screen.name()),
+            Action::FocusNext => "focus_next",
+            Action::FocusPrevious => "focus_previous",
+            Action::Zoom => "zoom",
+            Action::ScrollUp => "scroll_up",
+            Action::ScrollDown => "scroll_down",
+            Action::ScrollTop => "scroll_top",
+            Action::ScrollBottom => "scroll_bottom",
+            Action::ChartZoomIn => "chart_zoom_in",
+            Action::ChartZoomOut => "chart_zoom_out",
+            Action::ChartBack => "chart_back",
+            Action::ChartForward => "chart_forward",
+            Action::ChartLive => "chart_live",
+            Action::ToggleCursor => "cursor",
+            Action::CursorLeft => "cursor_left",
+            Action::CursorRight => "cursor_right",
+        };
+        name.to_string()
+    }
+
+    pub fn description(self) -> String {
+        let description = match self {
+            Action::Quit => "Quit",
+            Action::Back => "Close help / leave zoom / quit",
+            Action::Help => "Show this help",
+            Action::Refresh => "Refresh now",
+            Action::Pause => "Pause / resume the display",
+            Action::RefreshFaster => "Refresh more often",
+            Action::RefreshSlower => "Refresh less often",
+            Action::SaveSettings => "Save settings to the config file",
+            Action::CycleTheme => "Cycle themes",
+            Action::NextScreen => "Next screen",
+            Action::PreviousScreen => "Previous screen",
+            Action::Screen(screen) => return format!("{} screen", screen.title()),
+            Action::FocusNext => "Focus next panel",
+            Action::FocusPrevious => "Focus previous panel",
+            Action::Zoom => "Zoom focused panel",
+            Action::ScrollUp => "Scroll up",
+            Action::ScrollDown => "Scroll down",
+            Action::ScrollTop => "Scroll to top",
+            Action::ScrollBottom => "Scroll to bottom",
+            Action::ChartZoomIn => "Zoom charts in",
+            Action::ChartZoomOut => "Zoom charts out",
+            Action::ChartBack => "Scroll charts back",
+            Action::ChartForward => "Scroll charts forward",
+            Action::ChartLive => "Charts back to live",
+            Action::ToggleCursor => "Toggle chart cursor",
+            Action::CursorLeft => "Move cursor left",
+            Action::CursorRight => "Move cursor right",
+        };
+        description.to_string()
+    }
+}
+
+impl TryFrom<String> for Action {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        Action::all()
+            .into_iter()
+            .find(|action| action.name() == name)
+            .ok_or_else(|| format!("unknown action `{}`", name))
+    }
+}
+
+impl From<Action> for String {
+    fn from(action: Action) -> Self {
+        action.name()
+    }
+}
+
+/// A key with modifiers, written like `q`, `ctrl+c`, `shift+tab` or `pagedown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Shift is carried by the character itself (`G`, `?`) and by BackTab,
+        // and terminals disagree on whether they report it, so drop it there.
+        let (code, modifiers) = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => (
+                KeyCode::Char(c.to_ascii_uppercase()),
+                modifiers - KeyModifiers::SHIFT,
+            ),
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => {
+                (KeyCode::BackTab, modifiers - KeyModifiers::SHIFT)
+            }
+            KeyCode::BackTab => (KeyCode::BackTab, modifiers - KeyModifiers::SHIFT),
+            code => (code, modifiers),
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        *self == KeyChord::new(event.code, event.modifiers)
+    }
+}
+
+impl TryFrom<String> for KeyChord {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        // `+` is both the separator and a key of its own.
+        let (prefix, key) = if text == "+" {
+            ("", "+")
+        } else if let Some(prefix) = text.strip_suffix("++") {
+            (prefix, "+")
+        } else {
+            text.rsplit_once('+').unwrap_or(("", text.as_str()))
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in prefix.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier `{}` in key `{}`", modifier, text)),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key `{}`", text)),
+                },
+            },
+        };
+
+        Ok(KeyChord::new(code, modifiers))
+    }
+}
+
+impl From<KeyChord> for String {
+    fn from(chord: KeyChord) -> Self {
+        chord.to_string()
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Enter => write!(f, "enter"),
+            KeyCode::Esc => write!(f, "esc"),
+            KeyCode::Tab => write!(f, "tab"),
+            KeyCode::BackTab => write!(f, "shift+tab"),
+            KeyCode::Backspace => write!(f, "backspace"),
+            KeyCode::Delete => write!(f, "delete"),
+            KeyCode::Insert => write!(f, "insert"),
+            KeyCode::Home => write!(f, "home"),
+            KeyCode::End => write!(f, "end"),
+            KeyCode::PageUp => write!(f, "pageup"),
+            KeyCode::PageDown => write!(f, "pagedown"),
+            KeyCode::Up => write!(f, "up"),
+            KeyCode::Down => write!(f, "down"),
+            KeyCode::Left => write!(f, "left"),
+            KeyCode::Right => write!(f, "right"),
+            KeyCode::F(n) => write!(f, "f{}", n),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// One key or a list of keys for an action.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(KeyChord),
+    Many(Vec<KeyChord>),
+}
+
+// Written out by hand rather than derived as untagged, so a bad key name is
+// reported as such instead of "did not match any variant".
+impl<'de> Deserialize<'de> for KeyList {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct KeyListVisitor;
+
+        impl<'de> Visitor<'de> for KeyListVisitor {
+            type Value = KeyList;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a key like \"q\" or a list of keys")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<KeyList, E> {
+                KeyChord::try_from(value.to_string())
+                    .map(KeyList::One)
+                    .map_err(E::custom)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<KeyList, A::Error> {
+                Vec::<KeyChord>::deserialize(de::value::SeqAccessDeserializer::new(seq))
+                    .map(KeyList::Many)
+            }
+        }
+
+        deserializer.deserialize_any(KeyListVisitor)
+    }
+}
+
+impl KeyList {
+    fn to_vec(&self) -> Vec<KeyChord> {
+        match self {
+            KeyList::One(key) => vec![*key],
+            KeyList::Many(keys) => keys.clone(),
+        }
+    }
+}
+
+/// Extra bindings layered over the defaults.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyPreset {
+    #[default]
+    Default,
+    /// `hjkl` for charts and scrolling, `g`/`G` for top and bottom.
+    Vim,
+}
+
+/// The `[keys]` config section: an optional preset plus `action = "key"` or
+/// `action = ["key", ...]` entries replacing that action's default keys.
+/// Entries are keyed by name so a misspelled action is reported and skipped
+/// rather than rejecting the whole file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KeysConfig {
+    #[serde(default, skip_serializing_if = "is_default_preset")]
+    pub preset: KeyPreset,
+    #[serde(flatten)]
+    pub bindings: BTreeMap<String, KeyList>,
+}
+
+fn is_default_preset(preset: &KeyPreset) -> bool {
+    *preset == KeyPreset::Default
+}
+
+impl KeysConfig {
+    pub fn is_empty(&self) -> bool {
+        self.preset == KeyPreset::Default && self.bindings.is_empty()
+    }
+
+    /// Entries naming no known action.
+    pub fn unknown_actions(&self) -> impl Iterator<Item = &str> {
+        self.bindings
+            .keys()
+            .filter(|name| Action::try_from(name.to_string()).is_err())
+            .map(String::as_str)
+    }
+}
+
+fn default_bindings() -> Vec<(Action, Vec<&'static str>)> {
+    use Action::*;
+    let mut bindings = vec![
+        (Quit, vec!["q", "Q"]),
+        (Back, vec!["esc"]),
+        (Help, vec!["?"]),
+        (Refresh, vec!["r", "R"]),
+        (Pause, vec!["p", "P", "space"]),
+        (RefreshFaster, vec!["+", "="]),
+        (RefreshSlower, vec!["-"]),
+        (SaveSettings, vec!["ctrl+s", "S"]),
+        (CycleTheme, vec!["t", "T"]),
+        (NextScreen, vec!["tab"]),
+        (PreviousScreen, vec!["shift+tab"]),
+    ];
+    const DIGITS: [&str; 9] = ["1", "2", "3", "4", "5", "6", "7", "8", "9"];
+    bindings.extend(
+        crate::layout::Screen::ALL
+            .iter()
+            .zip(DIGITS)
+            .map(|(s, key)| (Action::Screen(*s), vec![key])),
+    );
+    bindings.extend([
+        (FocusNext, vec!["f"]),
+        (FocusPrevious, vec!["F"]),
+        (Zoom, vec!["z", "Z", "enter"]),
+        (ScrollUp, vec!["up", "pageup"]),
+        (ScrollDown, vec!["down", "pagedown"]),
+        (ScrollTop, vec!["home"]),
+        (ScrollBottom, vec![]),
+        (ChartZoomIn, vec!["]"]),
+        (ChartZoomOut, vec!["["]),
+        (ChartBack, vec!["left"]),
+        (ChartForward, vec!["right"]),
+        (ChartLive, vec!["end"]),
+        (ToggleCursor, vec!["c", "C"]),
+        (CursorLeft, vec![","]),
+        (CursorRight, vec!["."]),
+    ]);
+    bindings
+}
+
+fn vim_bindings() -> Vec<(Action, Vec<&'static str>)> {
+    use Action::*;
+    vec![
+        (ScrollUp, vec!["k", "ctrl+u"]),
+        (ScrollDown, vec!["j", "ctrl+d"]),
+        (ScrollTop, vec!["g"]),
+        (ScrollBottom, vec!["G"]),
+        (ChartBack, vec!["h"]),
+        (ChartForward, vec!["l"]),
+    ]
+}
+
+/// Resolved bindings for every action: the defaults, then the preset, then
+/// the user's `[keys]` entries.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<KeyChord>)>,
+}
+
+impl Keymap {
+    pub fn new(config: &KeysConfig) -> Self {
+        let parse = |keys: Vec<&str>| -> Vec<KeyChord> {
+            keys.into_iter()
+                .filter_map(|k| KeyChord::try_from(k.to_string()).ok())
+                .collect()
+        };
+
+        let mut bindings: Vec<(Action, Vec<KeyChord>)> = default_bindings()
+            .into_iter()
+            .map(|(action, keys)| (action, parse(keys)))
+            .collect();
+
+        if config.preset == KeyPreset::Vim {
+            for (action, keys) in vim_bindings() {
+                if let Some((_, existing)) = bindings.iter_mut().find(|(a, _)| *a == action) {
+                    existing.extend(parse(keys));
+                }
+            }
+        }
+
+        for (name, keys) in &config.bindings {
+            let Ok(action) = Action::try_from(name.clone()) else {
+                continue;
+            };
+            let keys = keys.to_vec();
+            // A key bound explicitly is taken away from whatever had it by default.
+            for (_, existing) in bindings.iter_mut() {
+                existing.retain(|k| !keys.contains(k));
+            }
+            if let Some((_, existing)) = bindings.iter_mut().find(|(a, _)| *a == action) {
+                *existing = keys;
+            }
+        }
+
+        Self { bindings }
+    }
+
+    pub fn action_for(&self, event: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.iter().any(|k| k.matches(event)))
+            .map(|(action, _)| *action)
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyChord] {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// The first key bound to `action`, for hints.
+    pub fn label(&self, action: Action) -> Option<String> {
+        self.keys(action).first().map(|k| k.to_string())
+    }
+
+    pub fn entries(&self) -> &[(Action, Vec<KeyChord>)] {
+        &self.bindings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(text: &str) -> Result<KeyChord, String> {
+        KeyChord::try_from(text.to_string())
+    }
+
+    #[test]
+    fn parses_key_chords() {
+        assert_eq!(
+            chord("q").unwrap(),
+            KeyChord::new(KeyCode::Char('q'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            chord("Ctrl+c").unwrap(),
+            KeyChord::new(KeyCode::Char('c'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            chord("alt+shift+F5").unwrap(),
+            KeyChord::new(KeyCode::F(5), KeyModifiers::ALT | KeyModifiers::SHIFT)
+        );
+        assert_eq!(
+            chord("pagedown").unwrap(),
+            KeyChord::new(KeyCode::PageDown, KeyModifiers::NONE)
+        );
+        assert_eq!(
+            chord("space").unwrap(),
+            KeyChord::new(KeyCode::Char(' '), KeyModifiers::NONE)
+        );
+    }
+
+    #[test]
+    fn plus_is_a_key_too() {
+        assert_eq!(
+            chord("+").unwrap(),
+            KeyChord::new(KeyCode::Char('+'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            chord("ctrl++").unwrap(),
+            KeyChord::new(KeyCode::Char('+'), KeyModifiers::CONTROL)
+        );
+    }
+
+    #[test]
+    fn shift_is_folded_into_the_key() {
+        assert_eq!(
+            chord("shift+g").unwrap(),
+            KeyChord::new(KeyCode::Char('G'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            chord("shift+tab").unwrap(),
+            KeyChord::new(KeyCode::BackTab, KeyModifiers::NONE)
+        );
+    }
+
+    #[test]
+    fn rejects_bad_key_chords() {
+        assert!(chord("hyper+x").unwrap_err().contains("unknown modifier"));
+        assert!(chord("ctrl+nope").unwrap_err().contains("unknown key"));
+        assert!(chord("f13").unwrap_err().contains("unknown key"));
+        assert!(chord("").unwrap_err().contains("unknown key"));
+    }
+}
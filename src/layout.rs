@@ -0,0 +1,452 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use serde::{Deserialize, Serialize};
+
+/// A dashboard panel that can be placed in the layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Panel {
+    #[serde(alias = "info")]
+    System,
+    Cpu,
+    Memory,
+    #[serde(alias = "disk")]
+    Disks,
+    Network,
+    Alerts,
+    Processes,
+    Interfaces,
+    Sensors,
+    Mounts,
+    Cgroups,
+    Services,
+}
+
+impl Panel {
+    /// Lower is more important; small terminals drop panels from the end.
+    fn priority(self) -> u8 {
+        match self {
+            Panel::Cpu => 0,
+            Panel::Memory => 1,
+            Panel::Processes => 2,
+            Panel::Alerts => 3,
+            Panel::Network => 4,
+            Panel::Disks => 5,
+            Panel::Sensors => 6,
+            Panel::Interfaces => 7,
+            Panel::Mounts => 8,
+            Panel::System => 9,
+            Panel::Cgroups => 10,
+            Panel::Services => 11,
+        }
+    }
+
+    /// Rows a panel needs, borders included, to show anything useful.
+    fn min_height(self) -> u16 {
+        match self {
+            Panel::Cpu => 7,
+            Panel::Memory => 6,
+            Panel::Processes => 6,
+            Panel::Network
+            | Panel::Interfaces
+            | Panel::Sensors
+            | Panel::Mounts
+            | Panel::Cgroups
+            | Panel::Services => 5,
+            Panel::Alerts | Panel::Disks | Panel::System => 4,
+        }
+    }
+}
+
+/// Terminal size classes the dashboard adapts to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Breakpoint {
+    /// Around 80x24: one column, abbreviated labels.
+    Compact,
+    #[default]
+    Normal,
+    /// Wide enough for panels to show their extra columns without zooming.
+    Wide,
+}
+
+impl Breakpoint {
+    pub fn for_area(area: Rect) -> Self {
+        if area.width < 100 || area.height < 28 {
+            Breakpoint::Compact
+        } else if area.width >= 180 {
+            Breakpoint::Wide
+        } else {
+            Breakpoint::Normal
+        }
+    }
+}
+
+/// A named screen with its own layout, switched between like tabs.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Screen {
+    #[default]
+    Overview,
+    Processes,
+    Network,
+    Disks,
+    Sensors,
+    Alerts,
+    Cgroups,
+    Services,
+}
+
+impl Screen {
+    pub const ALL: [Screen; 8] = [
+        Screen::Overview,
+        Screen::Processes,
+        Screen::Network,
+        Screen::Disks,
+        Screen::Sensors,
+        Screen::Alerts,
+        Screen::Cgroups,
+        Screen::Services,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Screen::Overview => "Overview",
+            Screen::Processes => "Processes",
+            Screen::Network => "Network",
+            Screen::Disks => "Disks",
+            Screen::Sensors => "Sensors",
+            Screen::Alerts => "Alerts",
+            Screen::Cgroups => "Cgroups",
+            Screen::Services => "Services",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Screen::Overview => "overview",
+            Screen::Processes => "processes",
+            Screen::Network => "network",
+            Screen::Disks => "disks",
+            Screen::Sensors => "sensors",
+            Screen::Alerts => "alerts",
+            Screen::Cgroups => "cgroups",
+            Screen::Services => "services",
+        }
+    }
+
+    pub fn index(self) -> usize {
+        Self::ALL.iter().position(|s| *s == self).unwrap_or(0)
+    }
+
+    pub fn next(self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    pub fn previous(self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    /// The layout used when the config doesn't define one for this screen.
+    pub fn default_layout(self) -> LayoutConfig {
+        use Panel::*;
+        let single = |rows: Vec<(u16, Vec<Vec<Panel>>)>| LayoutConfig {
+            rows: rows
+                .into_iter()
+                .map(|(size, columns)| Row {
+                    size,
+                    columns: columns
+                        .into_iter()
+                        .map(|panels| {
+                            Column::Panels(panels.into_iter().map(PanelSlot::Name).collect())
+                        })
+                        .collect(),
+                })
+                .collect(),
+        };
+
+        match self {
+            Screen::Overview => LayoutConfig::default(),
+            Screen::Processes => single(vec![(1, vec![vec![Processes]])]),
+            Screen::Network => single(vec![(1, vec![vec![Network]]), (2, vec![vec![Interfaces]])]),
+            Screen::Disks => single(vec![(1, vec![vec![Disks]]), (2, vec![vec![Mounts]])]),
+            Screen::Sensors => single(vec![(1, vec![vec![Sensors], vec![Cpu]])]),
+            Screen::Alerts => single(vec![(1, vec![vec![Alerts]])]),
+            Screen::Cgroups => single(vec![(1, vec![vec![Cgroups]])]),
+            Screen::Services => single(vec![(1, vec![vec![Services]])]),
+        }
+    }
+}
+
+/// A panel in a column, either just its name or `{ name = "cpu", size = 2 }`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum PanelSlot {
+    Name(Panel),
+    Sized { name: Panel, size: u16 },
+}
+
+// By hand rather than untagged, so a misspelt panel name is reported as
+// such instead of as "data did not match any variant".
+impl<'de> Deserialize<'de> for PanelSlot {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct SizedSlot {
+            name: Panel,
+            size: u16,
+        }
+
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = PanelSlot;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a panel name or a table with name and size")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, name: &str) -> Result<PanelSlot, E> {
+                Panel::deserialize(serde::de::value::StrDeserializer::new(name))
+                    .map(PanelSlot::Name)
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                map: A,
+            ) -> Result<PanelSlot, A::Error> {
+                let SizedSlot { name, size } =
+                    SizedSlot::deserialize(serde::de::value::MapAccessDeserializer::new(map))?;
+                Ok(PanelSlot::Sized { name, size })
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+impl PanelSlot {
+    fn panel(&self) -> Panel {
+        match self {
+            PanelSlot::Name(panel) | PanelSlot::Sized { name: panel, .. } => *panel,
+        }
+    }
+
+    fn size(&self) -> u16 {
+        match self {
+            PanelSlot::Name(_) => 1,
+            PanelSlot::Sized { size, .. } => *size,
+        }
+    }
+}
+
+/// A vertical stack of panels, either a bare list or `{ size = 40, panels = [...] }`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum Column {
+    Panels(Vec<PanelSlot>),
+    Sized { size: u16, panels: Vec<PanelSlot> },
+}
+
+impl<'de> Deserialize<'de> for Column {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct SizedColumn {
+            size: u16,
+            panels: Vec<PanelSlot>,
+        }
+
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = Column;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a list of panels or a table with size and panels")
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(self, seq: A) -> Result<Column, A::Error> {
+                Vec::deserialize(serde::de::value::SeqAccessDeserializer::new(seq))
+                    .map(Column::Panels)
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(self, map: A) -> Result<Column, A::Error> {
+                let SizedColumn { size, panels } =
+                    SizedColumn::deserialize(serde::de::value::MapAccessDeserializer::new(map))?;
+                Ok(Column::Sized { size, panels })
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+impl Column {
+    fn panels(&self) -> &[PanelSlot] {
+        match self {
+            Column::Panels(panels) | Column::Sized { panels, .. } => panels,
+        }
+    }
+
+    fn size(&self) -> u16 {
+        match self {
+            Column::Panels(_) => 1,
+            Column::Sized { size, .. } => *size,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Row {
+    /// Relative height of the row compared to its siblings.
+    #[serde(default = "default_size")]
+    pub size: u16,
+    pub columns: Vec<Column>,
+}
+
+fn default_size() -> u16 {
+    1
+}
+
+/// The `[layout]` config section: rows of columns of panels. Sizes are relative
+/// weights, so when a panel is hidden the remaining ones grow to fill its space.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutConfig {
+    pub rows: Vec<Row>,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        use Panel::*;
+        Self {
+            rows: vec![
+                Row {
+                    size: 35,
+                    columns: vec![Column::Panels(vec![PanelSlot::Name(System)])],
+                },
+                Row {
+                    size: 60,
+                    columns: vec![
+                        Column::Panels(vec![PanelSlot::Name(Cpu), PanelSlot::Name(Memory)]),
+                        Column::Panels(vec![PanelSlot::Name(Disks), PanelSlot::Name(Network)]),
+                    ],
+                },
+            ],
+        }
+    }
+}
+
+impl LayoutConfig {
+    /// Whether any row, column or panel is given a size of 0.
+    pub fn has_zero_size(&self) -> bool {
+        self.rows.iter().any(|row| {
+            row.size == 0
+                || row.columns.iter().any(|column| {
+                    column.size() == 0 || column.panels().iter().any(|slot| slot.size() == 0)
+                })
+        })
+    }
+
+    /// Every panel in the layout, in reading order.
+    pub fn panels(&self) -> Vec<Panel> {
+        self.rows
+            .iter()
+            .flat_map(|row| row.columns.iter())
+            .flat_map(|col| col.panels().iter().map(PanelSlot::panel))
+            .collect()
+    }
+
+    /// Splits `area` into one rect per visible panel.
+    pub fn resolve(&self, area: Rect, visible: impl Fn(Panel) -> bool) -> Vec<(Panel, Rect)> {
+        // Drop hidden panels first, then any columns and rows left empty.
+        let rows: Vec<(u16, Vec<SizedPanels>)> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let columns = row
+                    .columns
+                    .iter()
+                    .map(|col| {
+                        let panels = col.panels().iter().filter(|p| visible(p.panel())).collect();
+                        (col.size(), panels)
+                    })
+                    .filter(|(_, panels): &SizedPanels| !panels.is_empty())
+                    .collect();
+                (row.size, columns)
+            })
+            .filter(|(_, columns): &(u16, Vec<_>)| !columns.is_empty())
+            .collect();
+
+        let mut placed = Vec::new();
+        let row_areas = split(
+            area,
+            Direction::Vertical,
+            rows.iter().map(|(size, _)| *size),
+        );
+        for ((_, columns), row_area) in rows.iter().zip(row_areas.iter()) {
+            let col_areas = split(
+                *row_area,
+                Direction::Horizontal,
+                columns.iter().map(|(size, _)| *size),
+            );
+            for ((_, panels), col_area) in columns.iter().zip(col_areas.iter()) {
+                let panel_areas = split(
+                    *col_area,
+                    Direction::Vertical,
+                    panels.iter().map(|p| p.size()),
+                );
+                for (slot, panel_area) in panels.iter().zip(panel_areas.iter()) {
+                    placed.push((slot.panel(), *panel_area));
+                }
+            }
+        }
+
+        placed
+    }
+
+    /// Stacks the visible panels in a single column for small terminals,
+    /// dropping the least important ones that don't fit at their minimum height.
+    pub fn resolve_compact(
+        &self,
+        area: Rect,
+        visible: impl Fn(Panel) -> bool,
+    ) -> Vec<(Panel, Rect)> {
+        let mut panels: Vec<Panel> = Vec::new();
+        for panel in self.panels().into_iter().filter(|p| visible(*p)) {
+            if !panels.contains(&panel) {
+                panels.push(panel);
+            }
+        }
+
+        let mut by_priority = panels.clone();
+        by_priority.sort_by_key(|p| p.priority());
+        let mut kept = Vec::new();
+        let mut height = 0;
+        for panel in by_priority {
+            // Always keep one panel, even if the terminal is tinier than it wants.
+            if kept.is_empty() || height + panel.min_height() <= area.height {
+                height += panel.min_height();
+                kept.push(panel);
+            }
+        }
+        panels.retain(|p| kept.contains(p));
+
+        // Weighting by minimum height keeps every panel at or above it.
+        let areas = split(
+            area,
+            Direction::Vertical,
+            panels.iter().map(|p| p.min_height()),
+        );
+        panels.into_iter().zip(areas).collect()
+    }
+}
+
+type SizedPanels<'a> = (u16, Vec<&'a PanelSlot>);
+
+fn split(area: Rect, direction: Direction, sizes: impl Iterator<Item = u16>) -> Vec<Rect> {
+    let constraints: Vec<Constraint> = sizes.map(|s| Constraint::Fill(s.max(1))).collect();
+    Layout::default()
+        .direction(direction)
+        .constraints(constraints)
+        .split(area)
+        .to_vec()
+}
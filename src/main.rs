@@ -1,83 +1,201 @@
-mod app;
-mod config;
-mod system;
-mod theme;
-mod ui;
-
-use anyhow::Result;
-use app::App;
-use config::Config;
-use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use ratatui::{backend::CrosstermBackend, Terminal};
-use std::io;
-use std::time::Duration;
-
-fn main() -> Result<()> {
-    let config = Config::load()?;
-    let mut app = App::new(config);
-
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-
-    let result = run_app(&mut terminal, &mut app);
-
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
-
-    if let Err(err) = result {
-        eprintln!("Error: {:?}", err);
-    }
-
-    Ok(())
-}
-
-fn run_app<B: ratatui::backend::Backend>(
-    terminal: &mut Terminal<B>,
-    app: &mut App,
-) -> Result<()> {
-    loop {
-        terminal.draw(|f| ui::render(f, app))?;
-
-        if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') | KeyCode::Char('Q') => {
-                        app.quit();
-                    }
-                    KeyCode::Char('r') | KeyCode::Char('R') => {
-                        app.force_refresh();
-                    }
-                    KeyCode::Char('t') | KeyCode::Char('T') => {
-                        app.cycle_theme();
-                    }
-                    KeyCode::Esc => {
-                        app.quit();
-                    }
-                    _ => {}
-                }
-            }
-        }
-
-        app.update();
-
-        if app.should_quit {
-            break;
-        }
-    }
-
-    Ok(())
-}
-
+mod alerts;
+mod app;
+mod config;
+mod keys;
+mod layout;
+mod mouse;
+mod palette;
+mod system;
+mod theme;
+mod ui;
+
+use anyhow::Result;
+use app::App;
+use clap::Parser;
+use config::{CliArgs, Config};
+use crossterm::{
+    event::{
+        DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture, Event,
+        EventStream, KeyEventKind, MouseEventKind,
+    },
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use futures::StreamExt;
+use ratatui::{backend::CrosstermBackend, Terminal};
+use std::io::{self, Write};
+use std::sync::Arc;
+use std::time::Duration;
+use system::cgroups::ContainerLimits;
+use system::{Collection, Snapshot};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::time::{interval, sleep_until};
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    let args = CliArgs::parse();
+    if args.print_default_config {
+        print!("{}", Config::default_template()?);
+        return Ok(());
+    }
+    if let Some(path) = &args.import_theme {
+        print!("{}", palette::import_theme(path)?);
+        return Ok(());
+    }
+    if args.check_config {
+        let ok = Config::check_file(args);
+        std::process::exit(if ok { 0 } else { 1 });
+    }
+
+    let (config, warnings) = Config::load(args)?;
+    let container = ContainerLimits::detect();
+    let (collection, snapshots) = Collection::spawn(container.clone(), config.host_values);
+    let mut app = App::new(config, collection, container);
+    if let Some(warning) = warnings.first() {
+        let more = match warnings.len() {
+            1 => String::new(),
+            n => format!(" (and {} more, see --check-config)", n - 1),
+        };
+        app.set_status(format!("Config {}{}", warning, more));
+    }
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableFocusChange
+    )?;
+
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let result = run_app(&mut terminal, &mut app, snapshots).await;
+
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableFocusChange
+    )?;
+    terminal.show_cursor()?;
+
+    if let Err(err) = app.save_history() {
+        eprintln!("Failed to save metrics history: {}", err);
+    }
+    if let Err(err) = app.save_screen() {
+        eprintln!("Failed to save the active screen: {}", err);
+    }
+
+    if app.actions.is_dry_run() {
+        for entry in app.actions.log() {
+            println!("{} {}", entry.at.format("%Y-%m-%d %H:%M:%S"), entry.message);
+        }
+    }
+
+    if let Err(err) = result {
+        eprintln!("Error: {:?}", err);
+    }
+
+    Ok(())
+}
+
+/// How often the config file is checked for edits.
+const CONFIG_POLL: Duration = Duration::from_secs(1);
+
+/// Waits for input, the next collector falling due or a snapshot from the
+/// collection thread, so the screen is only redrawn when something changed
+/// and sampling keeps to schedule however much input arrives in between.
+async fn run_app<B: ratatui::backend::Backend + Write>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    mut snapshots: UnboundedReceiver<Arc<Snapshot>>,
+) -> Result<()> {
+    let mut events = EventStream::new();
+    let mut config_checks = interval(CONFIG_POLL);
+    let mut dirty = true;
+
+    loop {
+        if dirty {
+            terminal.draw(|f| ui::render(f, app))?;
+        }
+
+        tokio::select! {
+            event = events.next() => {
+                let Some(event) = event else {
+                    break;
+                };
+                dirty = handle_event(app, event?);
+            }
+            _ = sleep_until(app.scheduler.next_due().into()) => {
+                app.update();
+                dirty = false;
+            }
+            _ = config_checks.tick() => {
+                dirty = app.reload_config();
+            }
+            snapshot = snapshots.recv() => {
+                let Some(snapshot) = snapshot else {
+                    break;
+                };
+                app.apply_snapshot(snapshot);
+                dirty = true;
+            }
+        }
+
+        send_notifications(terminal.backend_mut(), app)?;
+
+        if app.should_quit {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Applies a terminal event, returning whether the screen needs redrawing.
+fn handle_event(app: &mut App, event: Event) -> bool {
+    match event {
+        Event::FocusGained => {
+            app.focused = true;
+            false
+        }
+        Event::FocusLost => {
+            app.focused = false;
+            false
+        }
+        Event::Resize(_, _) => true,
+        Event::Mouse(mouse_event) if mouse_event.kind == MouseEventKind::Moved => false,
+        Event::Mouse(mouse_event) => {
+            mouse::handle(app, mouse_event);
+            true
+        }
+        // Only presses, so terminals reporting releases don't act twice.
+        Event::Key(key) if key.kind == KeyEventKind::Press => match app.keymap.action_for(&key) {
+            Some(action) => {
+                app.perform(action);
+                true
+            }
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+fn send_notifications(out: &mut impl Write, app: &mut App) -> Result<()> {
+    if app.notifications.is_empty() {
+        return Ok(());
+    }
+
+    for notification in app.notifications.drain(..) {
+        match notification.escape_sequence() {
+            Some(sequence) => out.write_all(sequence.as_bytes())?,
+            None => notification.send_external(),
+        }
+    }
+    out.flush()?;
+
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use crate::app::App;
+use crate::keys::Action;
+use crate::layout::Panel;
+use crate::system::monitor::ProcessColumn;
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
+use std::collections::HashMap;
+
+/// Rows moved per wheel notch in scrollable panels.
+const WHEEL_STEP: usize = 3;
+
+/// The plot area of a history chart and the time range it was drawn with.
+#[derive(Debug, Clone, Copy)]
+pub struct ChartRegion {
+    pub graph: Rect,
+    pub start: u64,
+    pub window: u64,
+    pub newest: u64,
+}
+
+impl ChartRegion {
+    /// The timestamp under screen column `x`.
+    fn time_at(&self, x: u16) -> u64 {
+        let column = x
+            .saturating_sub(self.graph.x)
+            .min(self.graph.width.saturating_sub(1));
+        self.start + column as u64 * self.window / self.graph.width.max(1) as u64
+    }
+
+    fn clamp_column(&self, x: u16) -> u16 {
+        x.clamp(self.graph.left(), self.graph.right().saturating_sub(1))
+    }
+}
+
+/// Where things were drawn in the last frame, so mouse events can be
+/// resolved against what the user actually sees.
+#[derive(Debug, Default)]
+pub struct Regions {
+    pub panels: Vec<(Panel, Rect)>,
+    pub headers: Vec<(ProcessColumn, Rect)>,
+    pub footer: Vec<(Action, Rect)>,
+    pub charts: Vec<ChartRegion>,
+    /// Largest scroll offset of each scrollable panel at its drawn size.
+    pub scroll_limits: HashMap<Panel, usize>,
+}
+
+impl Regions {
+    fn panel_at(&self, position: Position) -> Option<Panel> {
+        self.panels
+            .iter()
+            .find(|(_, rect)| rect.contains(position))
+            .map(|(panel, _)| *panel)
+    }
+
+    fn header_at(&self, position: Position) -> Option<ProcessColumn> {
+        self.headers
+            .iter()
+            .find(|(_, rect)| rect.contains(position))
+            .map(|(column, _)| *column)
+    }
+
+    fn footer_at(&self, position: Position) -> Option<Action> {
+        self.footer
+            .iter()
+            .find(|(_, rect)| rect.contains(position))
+            .map(|(action, _)| *action)
+    }
+
+    fn chart_at(&self, position: Position) -> Option<ChartRegion> {
+        self.charts
+            .iter()
+            .find(|c| c.graph.contains(position))
+            .copied()
+    }
+}
+
+/// A drag in progress over a chart, in screen columns.
+#[derive(Debug, Clone, Copy)]
+pub struct ChartDrag {
+    pub chart: ChartRegion,
+    pub from: u16,
+    pub to: u16,
+}
+
+impl ChartDrag {
+    /// The selected columns, left to right.
+    pub fn columns(&self) -> (u16, u16) {
+        (self.from.min(self.to), self.from.max(self.to))
+    }
+}
+
+pub fn handle(app: &mut App, event: MouseEvent) {
+    let position = Position::new(event.column, event.row);
+
+    match event.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            let (footer, header, chart, panel) = {
+                let regions = app.regions.borrow();
+                (
+                    regions.footer_at(position),
+                    regions.header_at(position),
+                    regions.chart_at(position),
+                    regions.panel_at(position),
+                )
+            };
+
+            if let Some(action) = footer {
+                app.perform(action);
+                return;
+            }
+            if let Some(panel) = panel {
+                app.focus = Some(panel);
+            }
+            if let Some(column) = header {
+                app.sort_processes_by(column);
+            } else if let Some(chart) = chart {
+                app.drag = Some(ChartDrag {
+                    chart,
+                    from: event.column,
+                    to: event.column,
+                });
+            }
+        }
+        MouseEventKind::Drag(MouseButton::Left) => {
+            if let Some(drag) = app.drag.as_mut() {
+                drag.to = drag.chart.clamp_column(event.column);
+            }
+        }
+        MouseEventKind::Up(MouseButton::Left) => {
+            if let Some(drag) = app.drag.take() {
+                let chart = drag.chart;
+                let (left, right) = drag.columns();
+                if left == right {
+                    // A plain click puts the readout cursor under the pointer.
+                    let end = chart.start + chart.window;
+                    app.chart
+                        .place_cursor(end.saturating_sub(chart.time_at(left)));
+                } else {
+                    app.chart
+                        .select(chart.time_at(left), chart.time_at(right), chart.newest);
+                }
+            }
+        }
+        MouseEventKind::ScrollUp => scroll(app, position, -1),
+        MouseEventKind::ScrollDown => scroll(app, position, 1),
+        _ => {}
+    }
+}
+
+fn scroll(app: &mut App, position: Position, direction: isize) {
+    let (panel, on_chart) = {
+        let regions = app.regions.borrow();
+        (
+            regions.panel_at(position),
+            regions.chart_at(position).is_some(),
+        )
+    };
+
+    if on_chart {
+        // Wheel up goes back in time, like scrolling up a log.
+        if direction < 0 {
+            app.scroll_chart_back();
+        } else {
+            app.chart.scroll_forward();
+        }
+        return;
+    }
+
+    if let Some(panel) = panel {
+        app.scroll_panel(panel, direction * WHEEL_STEP as isize);
+    }
+}
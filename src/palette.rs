@@ -0,0 +1,472 @@
+use crate::theme::ThemeFile;
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Terminal color scheme formats `--import-theme` reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Alacritty,
+    Kitty,
+    WezTerm,
+    Base16,
+}
+
+impl Format {
+    fn name(self) -> &'static str {
+        match self {
+            Format::Alacritty => "Alacritty",
+            Format::Kitty => "Kitty",
+            Format::WezTerm => "WezTerm",
+            Format::Base16 => "base16",
+        }
+    }
+}
+
+/// A terminal's colors: background, foreground and the 16 ANSI colors, in
+/// the usual order of black, red, green, yellow, blue, magenta, cyan and
+/// white, then their bright versions.
+struct Palette {
+    background: [u8; 3],
+    foreground: [u8; 3],
+    ansi: [Option<[u8; 3]>; 16],
+}
+
+impl Palette {
+    /// The normal ANSI color `index`, or its bright version when the scheme
+    /// leaves the normal one out.
+    fn ansi(&self, index: usize) -> Option<[u8; 3]> {
+        self.ansi[index].or(self.ansi[index + 8])
+    }
+
+    /// The roles of a theme, taken from the ANSI colors that usually play
+    /// them: blue for highlights, magenta for labels, green, yellow and red
+    /// for usage, and bright black for borders.
+    fn theme(&self) -> ThemeFile {
+        let border = self.ansi[8]
+            .filter(|border| *border != self.background)
+            .unwrap_or_else(|| blend(self.background, self.foreground, 0.3));
+        ThemeFile {
+            background: Some(self.background),
+            foreground: Some(self.foreground),
+            primary: self.ansi(4),
+            secondary: self.ansi(5),
+            success: self.ansi(2),
+            warning: self.ansi(3),
+            danger: self.ansi(1),
+            border: Some(border),
+            ..ThemeFile::default()
+        }
+    }
+}
+
+/// Reads the color scheme at `path` and writes it out as a theme file.
+pub fn import_theme(path: &Path) -> Result<String> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("could not read {}", path.display()))?;
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let keys = match extension.as_str() {
+        "toml" => flatten_toml(&content)?,
+        "yml" | "yaml" => flatten_yaml(&content),
+        _ => flatten_kitty(&content),
+    };
+
+    let (format, palette) = read_palette(&keys)?;
+    let theme = toml::to_string(&palette.theme())?;
+
+    let name = ["scheme", "name", "metadata.name", "palette.scheme"]
+        .iter()
+        .find_map(|key| keys.get(*key))
+        .cloned()
+        .or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+        })
+        .unwrap_or_default();
+    let file_name: String = name
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    Ok(format!(
+        "# {} imported from the {} color scheme in {}.\n\
+         # Save it as ~/.config/rust-tui-monitor/themes/{}.toml to use it.\n{}",
+        name,
+        format.name(),
+        path.file_name().unwrap_or_default().to_string_lossy(),
+        file_name,
+        theme
+    ))
+}
+
+fn read_palette(keys: &BTreeMap<String, String>) -> Result<(Format, Palette)> {
+    let has = |key: &str| keys.contains_key(key);
+    let (format, background, foreground, ansi): (Format, &str, &str, Vec<String>) = if has("base00")
+        || has("palette.base00")
+    {
+        let prefix = if has("base00") { "" } else { "palette." };
+        // base16 names colors by role rather than ANSI slot; these are
+        // the slots its own terminal templates put them in.
+        let slots = [
+            "00", "08", "0b", "0a", "0d", "0e", "0c", "05", "03", "08", "0b", "0a", "0d", "0e",
+            "0c", "07",
+        ];
+        let ansi = slots
+            .iter()
+            .map(|slot| format!("{}base{}", prefix, slot))
+            .collect();
+        (Format::Base16, "base00", "base05", ansi)
+    } else if has("colors.primary.background") {
+        let names = [
+            "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+        ];
+        let ansi = ["normal", "bright"]
+            .iter()
+            .flat_map(|group| {
+                names
+                    .iter()
+                    .map(move |name| format!("colors.{}.{}", group, name))
+            })
+            .collect();
+        (
+            Format::Alacritty,
+            "colors.primary.background",
+            "colors.primary.foreground",
+            ansi,
+        )
+    } else if has("colors.background") || has("colors.ansi.0") {
+        let ansi = ["ansi", "brights"]
+            .iter()
+            .flat_map(|group| (0..8).map(move |i| format!("colors.{}.{}", group, i)))
+            .collect();
+        (
+            Format::WezTerm,
+            "colors.background",
+            "colors.foreground",
+            ansi,
+        )
+    } else if has("color0") || has("background") {
+        let ansi = (0..16).map(|i| format!("color{}", i)).collect();
+        (Format::Kitty, "background", "foreground", ansi)
+    } else {
+        bail!("not a color scheme this can read; expected an Alacritty, Kitty, WezTerm or base16 file");
+    };
+
+    // base16 keys carry the `palette.` prefix in the newer scheme format.
+    let lookup = |key: &str| -> Result<Option<[u8; 3]>> {
+        let key = match format {
+            Format::Base16 if !has(key) => format!("palette.{}", key),
+            _ => key.to_string(),
+        };
+        keys.get(&key)
+            .map(|value| {
+                parse_color(value).ok_or_else(|| anyhow!("`{}` is not a color: {}", key, value))
+            })
+            .transpose()
+    };
+
+    let mut colors = [None; 16];
+    for (slot, key) in colors.iter_mut().zip(&ansi) {
+        *slot = lookup(key)?;
+    }
+    let background = lookup(background)?.or(colors[0]);
+    let foreground = lookup(foreground)?.or(colors[7]);
+    let (Some(background), Some(foreground)) = (background, foreground) else {
+        bail!(
+            "the {} scheme has no background or foreground color",
+            format.name()
+        );
+    };
+
+    Ok((
+        format,
+        Palette {
+            background,
+            foreground,
+            ansi: colors,
+        },
+    ))
+}
+
+/// Parses `#rrggbb`, `0xrrggbb`, bare `rrggbb` (as base16 writes them) and
+/// `#rgb`.
+fn parse_color(value: &str) -> Option<[u8; 3]> {
+    let hex = value.trim();
+    let hex = hex
+        .strip_prefix('#')
+        .or_else(|| hex.strip_prefix("0x"))
+        .or_else(|| hex.strip_prefix("0X"))
+        .unwrap_or(hex);
+    if !hex.is_ascii() {
+        return None;
+    }
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+    match hex.len() {
+        6 => Some([
+            channel(&hex[0..2])?,
+            channel(&hex[2..4])?,
+            channel(&hex[4..6])?,
+        ]),
+        3 => {
+            let short = |i: usize| channel(&hex[i..i + 1]).map(|c| c * 17);
+            Some([short(0)?, short(1)?, short(2)?])
+        }
+        _ => None,
+    }
+}
+
+/// `t` of the way from `from` to `to`.
+fn blend(from: [u8; 3], to: [u8; 3], t: f32) -> [u8; 3] {
+    let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    [
+        channel(from[0], to[0]),
+        channel(from[1], to[1]),
+        channel(from[2], to[2]),
+    ]
+}
+
+/// Every string or number in a TOML file by its dotted path, with array
+/// items numbered: `colors.ansi.0`.
+fn flatten_toml(content: &str) -> Result<BTreeMap<String, String>> {
+    fn walk(prefix: &str, value: &toml::Value, keys: &mut BTreeMap<String, String>) {
+        let path = |key: &str| {
+            if prefix.is_empty() {
+                key.to_lowercase()
+            } else {
+                format!("{}.{}", prefix, key.to_lowercase())
+            }
+        };
+        match value {
+            toml::Value::Table(table) => {
+                for (key, value) in table {
+                    walk(&path(key), value, keys);
+                }
+            }
+            toml::Value::Array(items) => {
+                for (index, value) in items.iter().enumerate() {
+                    walk(&path(&index.to_string()), value, keys);
+                }
+            }
+            toml::Value::String(s) => {
+                keys.insert(prefix.to_string(), s.clone());
+            }
+            other => {
+                keys.insert(prefix.to_string(), other.to_string());
+            }
+        }
+    }
+
+    let value: toml::Value =
+        toml::from_str(content).map_err(|err| anyhow!(err.message().to_string()))?;
+    let mut keys = BTreeMap::new();
+    walk("", &value, &mut keys);
+    Ok(keys)
+}
+
+/// The `key: value` pairs of a YAML file by their dotted path, following
+/// nesting by indentation. That covers the mappings of strings color schemes
+/// are written in; lists and multi-line values are skipped.
+fn flatten_yaml(content: &str) -> BTreeMap<String, String> {
+    let mut keys = BTreeMap::new();
+    let mut parents: Vec<(usize, String)> = Vec::new();
+    for line in content.lines() {
+        let line = strip_comment(line);
+        let text = line.trim_start();
+        if text.is_empty() || text.starts_with('-') || text == "---" {
+            continue;
+        }
+        let Some((key, value)) = text.split_once(':') else {
+            continue;
+        };
+        let indent = line.len() - text.len();
+        while parents.last().is_some_and(|(level, _)| *level >= indent) {
+            parents.pop();
+        }
+
+        let key = unquote(key).to_lowercase();
+        let path = parents
+            .iter()
+            .map(|(_, key)| key.as_str())
+            .chain(std::iter::once(key.as_str()))
+            .collect::<Vec<_>>()
+            .join(".");
+        let value = unquote(value);
+        if value.is_empty() {
+            parents.push((indent, key));
+        } else {
+            keys.insert(path, value.to_string());
+        }
+    }
+    keys
+}
+
+/// The `key value` lines of a Kitty config.
+fn flatten_kitty(content: &str) -> BTreeMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once(char::is_whitespace))
+        .map(|(key, value)| (key.to_lowercase(), value.trim().to_string()))
+        .collect()
+}
+
+/// A YAML line up to a `#` comment outside quotes.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '#') if previous.is_whitespace() => return &line[..index],
+            _ => {}
+        }
+        previous = c;
+    }
+    line
+}
+
+fn unquote(s: &str) -> &str {
+    let s = s.trim();
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .or_else(|| s.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')))
+        .unwrap_or(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_color_reads_every_notation() {
+        assert_eq!(parse_color("#1e1e2e"), Some([0x1e, 0x1e, 0x2e]));
+        assert_eq!(parse_color("0xFF8000"), Some([0xff, 0x80, 0x00]));
+        assert_eq!(parse_color(" 282a36 "), Some([0x28, 0x2a, 0x36]));
+        assert_eq!(parse_color("#f0a"), Some([0xff, 0x00, 0xaa]));
+    }
+
+    #[test]
+    fn parse_color_rejects_other_text() {
+        assert_eq!(parse_color("#12345"), None);
+        assert_eq!(parse_color("#gggggg"), None);
+        assert_eq!(parse_color("red"), None);
+        assert_eq!(parse_color("#ffé"), None);
+        assert_eq!(parse_color(""), None);
+    }
+
+    #[test]
+    fn strip_comment_cuts_at_a_comment() {
+        assert_eq!(
+            strip_comment("base00: 1d2021 # background"),
+            "base00: 1d2021 "
+        );
+        assert_eq!(strip_comment("color: #abc"), "color: ");
+        assert_eq!(strip_comment("# only a comment"), "");
+    }
+
+    #[test]
+    fn strip_comment_keeps_hashes_in_quotes_and_values() {
+        assert_eq!(strip_comment("base00: \"#1d2021\""), "base00: \"#1d2021\"");
+        assert_eq!(strip_comment("name: 'a # b'"), "name: 'a # b'");
+        assert_eq!(strip_comment("key: a#b"), "key: a#b");
+    }
+
+    #[test]
+    fn flatten_yaml_follows_nesting() {
+        let content = "\
+scheme: \"Gruvbox\" # dark
+colors:
+  primary:
+    background: '#282828'
+    foreground: '#ebdbb2'
+  normal:
+    red: '#cc241d'
+";
+        let keys = flatten_yaml(content);
+        assert_eq!(keys.get("scheme").map(String::as_str), Some("Gruvbox"));
+        assert_eq!(
+            keys.get("colors.primary.background").map(String::as_str),
+            Some("#282828")
+        );
+        assert_eq!(
+            keys.get("colors.normal.red").map(String::as_str),
+            Some("#cc241d")
+        );
+        assert_eq!(keys.len(), 4);
+    }
+
+    #[test]
+    fn flatten_yaml_skips_what_it_cannot_read() {
+        let content = "---\n- item\njust text\n\n# comment\n";
+        assert!(flatten_yaml(content).is_empty());
+    }
+
+    #[test]
+    fn read_palette_detects_each_format() {
+        let cases = [
+            (
+                keys(&[("base00", "282828"), ("base05", "ebdbb2")]),
+                Format::Base16,
+            ),
+            (
+                keys(&[("palette.base00", "282828"), ("palette.base05", "ebdbb2")]),
+                Format::Base16,
+            ),
+            (
+                keys(&[
+                    ("colors.primary.background", "#282828"),
+                    ("colors.primary.foreground", "#ebdbb2"),
+                ]),
+                Format::Alacritty,
+            ),
+            (
+                keys(&[
+                    ("colors.background", "#282828"),
+                    ("colors.foreground", "#ebdbb2"),
+                ]),
+                Format::WezTerm,
+            ),
+            (
+                keys(&[("background", "#282828"), ("foreground", "#ebdbb2")]),
+                Format::Kitty,
+            ),
+        ];
+        for (keys, expected) in cases {
+            let (format, palette) = read_palette(&keys).unwrap();
+            assert_eq!(format, expected);
+            assert_eq!(palette.background, [0x28, 0x28, 0x28]);
+            assert_eq!(palette.foreground, [0xeb, 0xdb, 0xb2]);
+        }
+    }
+
+    #[test]
+    fn read_palette_falls_back_to_ansi_colors() {
+        let keys = keys(&[("color0", "#000000"), ("color7", "#c0c0c0")]);
+        let (format, palette) = read_palette(&keys).unwrap();
+        assert_eq!(format, Format::Kitty);
+        assert_eq!(palette.background, [0, 0, 0]);
+        assert_eq!(palette.foreground, [0xc0, 0xc0, 0xc0]);
+    }
+
+    #[test]
+    fn read_palette_rejects_unknown_files() {
+        assert!(read_palette(&keys(&[("font_size", "12")])).is_err());
+        assert!(read_palette(&keys(&[("background", "dark"), ("foreground", "#fff")])).is_err());
+        assert!(read_palette(&keys(&[("color1", "#ff0000")])).is_err());
+    }
+}
@@ -0,0 +1,519 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Where the cgroup v2 hierarchy is mounted.
+pub const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// How deep below the root cgroups are listed. Kubernetes puts containers
+/// four levels down: `kubepods.slice/<qos>.slice/<pod>.slice/<container>`.
+const MAX_DEPTH: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CgroupKind {
+    Slice,
+    Service,
+    Scope,
+    /// A container, named by the runtime that started it.
+    Container(&'static str),
+    Other,
+}
+
+#[derive(Debug, Clone)]
+pub struct CgroupStats {
+    /// Path below the cgroup root, such as `/system.slice/nginx.service`.
+    pub path: String,
+    /// The last path component, or the runtime and short ID of a container.
+    pub name: String,
+    pub kind: CgroupKind,
+    /// 1 for the root's children, 2 for theirs and so on.
+    pub depth: usize,
+    /// Percent of one CPU, like process CPU usage.
+    pub cpu_usage: f32,
+    pub memory_current: u64,
+    pub memory_max: Option<u64>,
+    /// Bytes per second, summed over all devices.
+    pub io_read_rate: f64,
+    pub io_write_rate: f64,
+    pub pids_current: u64,
+    pub pids_max: Option<u64>,
+    /// Processes directly in this cgroup, from `cgroup.procs`; those in
+    /// child cgroups are listed under the children.
+    pub processes: Vec<Member>,
+}
+
+/// A process in a cgroup.
+#[derive(Debug, Clone)]
+pub struct Member {
+    pub pid: u32,
+    /// The process name from `/proc/<pid>/comm`.
+    pub name: String,
+}
+
+impl CgroupStats {
+    /// Memory use as a percent of the cgroup's limit, if it has one.
+    pub fn memory_percent(&self) -> Option<f32> {
+        self.memory_max
+            .filter(|max| *max > 0)
+            .map(|max| self.memory_current as f32 / max as f32 * 100.0)
+    }
+}
+
+/// Counters read on the previous pass, to turn into rates.
+#[derive(Debug, Clone, Copy, Default)]
+struct Counters {
+    cpu_usec: u64,
+    read_bytes: u64,
+    write_bytes: u64,
+}
+
+/// Reads every cgroup's CPU, memory, IO and PID use from the cgroup v2
+/// filesystem. Nothing is found on cgroup v1 hosts or off Linux.
+pub struct CgroupMonitor {
+    root: PathBuf,
+    previous: HashMap<String, Counters>,
+    last_update: Instant,
+}
+
+impl CgroupMonitor {
+    pub fn new() -> Self {
+        Self {
+            root: PathBuf::from(CGROUP_ROOT),
+            previous: HashMap::new(),
+            last_update: Instant::now(),
+        }
+    }
+
+    /// Whether the cgroup v2 hierarchy is mounted at `CGROUP_ROOT`.
+    pub fn available() -> bool {
+        Path::new(CGROUP_ROOT).join("cgroup.controllers").exists()
+    }
+
+    /// Every cgroup with processes in it, parents before their children.
+    pub fn collect(&mut self) -> Vec<CgroupStats> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_update).as_secs_f64();
+        self.last_update = now;
+
+        let mut stats = Vec::new();
+        let mut counters = HashMap::new();
+        if Self::available() {
+            let root = self.root.clone();
+            self.walk(&root, "", 1, elapsed, &mut stats, &mut counters);
+        }
+        self.previous = counters;
+        stats
+    }
+
+    fn walk(
+        &self,
+        dir: &Path,
+        path: &str,
+        depth: usize,
+        elapsed: f64,
+        stats: &mut Vec<CgroupStats>,
+        counters: &mut HashMap<String, Counters>,
+    ) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        let mut children: Vec<(String, PathBuf)> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+            .map(|entry| {
+                (
+                    entry.file_name().to_string_lossy().to_string(),
+                    entry.path(),
+                )
+            })
+            .collect();
+        children.sort();
+
+        let parent = path.rsplit('/').next().unwrap_or("");
+        for (component, child) in children {
+            let child_path = format!("{}/{}", path, component);
+            let cgroup = read_cgroup(&child);
+            let pids_current = cgroup.pids_current;
+            // A cgroup without the pids controller can't say whether it's
+            // empty, so it's kept.
+            if pids_current == Some(0) {
+                continue;
+            }
+
+            let previous = self.previous.get(&child_path);
+            let rate = |now: u64, before: Option<u64>| match before {
+                Some(before) if elapsed > 0.0 => now.saturating_sub(before) as f64 / elapsed,
+                _ => 0.0,
+            };
+            let (kind, name) = classify(&component, parent);
+            stats.push(CgroupStats {
+                path: child_path.clone(),
+                name,
+                kind,
+                depth,
+                cpu_usage: (rate(cgroup.counters.cpu_usec, previous.map(|p| p.cpu_usec)) / 10_000.0)
+                    as f32,
+                memory_current: cgroup.memory_current,
+                memory_max: cgroup.memory_max,
+                io_read_rate: rate(cgroup.counters.read_bytes, previous.map(|p| p.read_bytes)),
+                io_write_rate: rate(cgroup.counters.write_bytes, previous.map(|p| p.write_bytes)),
+                pids_current: pids_current.unwrap_or(0),
+                pids_max: cgroup.pids_max,
+                processes: read_members(&child),
+            });
+            counters.insert(child_path.clone(), cgroup.counters);
+
+            self.walk(&child, &child_path, depth + 1, elapsed, stats, counters);
+        }
+    }
+}
+
+/// The limits of the cgroup the monitor runs in, when that's a container.
+/// Host-wide memory and CPU counts overstate what a container can use.
+#[derive(Debug, Clone)]
+pub struct ContainerLimits {
+    /// The runtime that started the container, or `container` when unknown.
+    pub runtime: String,
+    /// The container's cgroup directory; `None` without cgroup v2.
+    dir: Option<PathBuf>,
+    /// CPUs the container may use, from `cpu.max` or a narrower cpuset.
+    pub cpus: Option<f64>,
+    pub memory_max: Option<u64>,
+    pub swap_max: Option<u64>,
+}
+
+impl ContainerLimits {
+    /// The monitor's own container, if it runs in one.
+    pub fn detect() -> Option<Self> {
+        let runtime = container_runtime()?;
+        let dir = own_cgroup();
+        let limit = |file: &str| dir.as_ref().and_then(|dir| read_limit(&dir.join(file)));
+        let quota = dir
+            .as_ref()
+            .and_then(|dir| fs::read_to_string(dir.join("cpu.max")).ok())
+            .and_then(|content| {
+                let mut fields = content.split_whitespace();
+                let quota: f64 = fields.next()?.parse().ok()?;
+                let period: f64 = fields.next()?.parse().ok()?;
+                (period > 0.0).then(|| quota / period)
+            });
+        let online = read_cpu_list(Path::new("/sys/devices/system/cpu/online"));
+        let cpuset = dir
+            .as_ref()
+            .and_then(|dir| read_cpu_list(&dir.join("cpuset.cpus.effective")))
+            .filter(|count| online.is_some_and(|online| count < &online))
+            .map(|count| count as f64);
+        let cpus = match (quota, cpuset) {
+            (Some(quota), Some(cpuset)) => Some(quota.min(cpuset)),
+            (quota, cpuset) => quota.or(cpuset),
+        };
+
+        Some(Self {
+            runtime,
+            cpus,
+            memory_max: limit("memory.max"),
+            swap_max: limit("memory.swap.max"),
+            dir,
+        })
+    }
+
+    /// Whether the container's cgroup could be read, so usage can be put in
+    /// terms of it.
+    pub fn readable(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    /// CPU time used by the container so far, in microseconds.
+    pub fn cpu_usec(&self) -> Option<u64> {
+        read_keyed(&self.dir.as_ref()?.join("cpu.stat"), "usage_usec")
+    }
+
+    /// Memory in use, leaving out page cache the kernel can reclaim, the way
+    /// `docker stats` counts it.
+    pub fn memory_current(&self) -> Option<u64> {
+        let dir = self.dir.as_ref()?;
+        let current = read_value(&dir.join("memory.current"))?;
+        let inactive = read_keyed(&dir.join("memory.stat"), "inactive_file").unwrap_or(0);
+        Some(current.saturating_sub(inactive))
+    }
+
+    pub fn swap_current(&self) -> Option<u64> {
+        read_value(&self.dir.as_ref()?.join("memory.swap.current"))
+    }
+}
+
+/// The runtime the monitor is contained by, going by the marker files and
+/// variables runtimes leave, then by the cgroup of PID 1.
+fn container_runtime() -> Option<String> {
+    if Path::new("/.dockerenv").exists() {
+        return Some("docker".to_string());
+    }
+    if Path::new("/run/.containerenv").exists() {
+        return Some("podman".to_string());
+    }
+    // systemd-nspawn, LXC and podman set this for the container's init.
+    if let Some(name) = env::var("container").ok().filter(|name| !name.is_empty()) {
+        return Some(name);
+    }
+    if env::var_os("KUBERNETES_SERVICE_HOST").is_some() {
+        return Some("kubernetes".to_string());
+    }
+    let cgroups = fs::read_to_string("/proc/1/cgroup").ok()?;
+    [
+        ("kubepods", "kubernetes"),
+        ("docker", "docker"),
+        ("libpod", "podman"),
+        ("lxc", "lxc"),
+    ]
+    .into_iter()
+    .find(|(marker, _)| cgroups.contains(marker))
+    .map(|(_, runtime)| runtime.to_string())
+}
+
+/// This process's cgroup v2 directory. Inside a cgroup namespace the path in
+/// `/proc/self/cgroup` is `/` and the mount is the container's own cgroup.
+fn own_cgroup() -> Option<PathBuf> {
+    let root = Path::new(CGROUP_ROOT);
+    if !root.join("cgroup.controllers").exists() {
+        return None;
+    }
+    let cgroups = fs::read_to_string("/proc/self/cgroup").ok()?;
+    let path = cgroups.lines().find_map(|line| line.strip_prefix("0::"))?;
+    let dir = root.join(path.trim().trim_start_matches('/'));
+    Some(if dir.is_dir() {
+        dir
+    } else {
+        root.to_path_buf()
+    })
+}
+
+/// How many CPUs a list like `0-3,6` names.
+fn read_cpu_list(path: &Path) -> Option<usize> {
+    let content = fs::read_to_string(path).ok()?;
+    let mut count = 0;
+    for range in content.trim().split(',').filter(|range| !range.is_empty()) {
+        count += match range.split_once('-') {
+            Some((first, last)) => {
+                last.parse::<usize>()
+                    .ok()?
+                    .checked_sub(first.parse().ok()?)?
+                    + 1
+            }
+            None => range.parse::<usize>().map(|_| 1).ok()?,
+        };
+    }
+    (count > 0).then_some(count)
+}
+
+/// What one pass reads from a cgroup directory.
+struct Reading {
+    counters: Counters,
+    memory_current: u64,
+    memory_max: Option<u64>,
+    pids_current: Option<u64>,
+    pids_max: Option<u64>,
+}
+
+fn read_cgroup(dir: &Path) -> Reading {
+    let cpu_usec = read_keyed(&dir.join("cpu.stat"), "usage_usec").unwrap_or(0);
+    let (read_bytes, write_bytes) = read_io(&dir.join("io.stat"));
+    Reading {
+        counters: Counters {
+            cpu_usec,
+            read_bytes,
+            write_bytes,
+        },
+        memory_current: read_value(&dir.join("memory.current")).unwrap_or(0),
+        memory_max: read_limit(&dir.join("memory.max")),
+        pids_current: read_value(&dir.join("pids.current")),
+        pids_max: read_limit(&dir.join("pids.max")),
+    }
+}
+
+/// The processes listed in a cgroup's `cgroup.procs`, by PID.
+fn read_members(dir: &Path) -> Vec<Member> {
+    let Ok(content) = fs::read_to_string(dir.join("cgroup.procs")) else {
+        return Vec::new();
+    };
+    let mut members: Vec<Member> = content
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        // A process that exited since the list was read has no `comm` left.
+        .filter_map(|pid: u32| {
+            let comm = fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+            Some(Member {
+                pid,
+                name: comm.trim().to_string(),
+            })
+        })
+        .collect();
+    members.sort_by_key(|member| member.pid);
+    members
+}
+
+/// A file holding a single number.
+pub fn read_value(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// A limit file holding a number, or `max` for no limit.
+pub fn read_limit(path: &Path) -> Option<u64> {
+    match fs::read_to_string(path).ok()?.trim() {
+        "max" => None,
+        value => value.parse().ok(),
+    }
+}
+
+/// The value of `key` in a file of `key value` lines, like `cpu.stat`.
+pub fn read_keyed(path: &Path, key: &str) -> Option<u64> {
+    fs::read_to_string(path).ok()?.lines().find_map(|line| {
+        line.strip_prefix(key)?
+            .strip_prefix(' ')?
+            .trim()
+            .parse()
+            .ok()
+    })
+}
+
+/// Bytes read and written, summed over the devices in `io.stat`.
+fn read_io(path: &Path) -> (u64, u64) {
+    let Ok(content) = fs::read_to_string(path) else {
+        return (0, 0);
+    };
+    let mut totals = (0, 0);
+    for field in content.split_whitespace() {
+        if let Some(bytes) = field
+            .strip_prefix("rbytes=")
+            .and_then(|v| v.parse::<u64>().ok())
+        {
+            totals.0 += bytes;
+        } else if let Some(bytes) = field
+            .strip_prefix("wbytes=")
+            .and_then(|v| v.parse::<u64>().ok())
+        {
+            totals.1 += bytes;
+        }
+    }
+    totals
+}
+
+/// What a cgroup directory holds, going by the names systemd and the
+/// container runtimes give them, and the name to show for it.
+fn classify(component: &str, parent: &str) -> (CgroupKind, String) {
+    if let Some((runtime, id)) = container_id(component, parent) {
+        return (
+            CgroupKind::Container(runtime),
+            format!("{} {}", runtime, id),
+        );
+    }
+    let kind = if component.ends_with(".slice") {
+        CgroupKind::Slice
+    } else if component.ends_with(".service") {
+        CgroupKind::Service
+    } else if component.ends_with(".scope") {
+        CgroupKind::Scope
+    } else {
+        CgroupKind::Other
+    };
+    (kind, component.to_string())
+}
+
+/// The runtime and short ID of a container cgroup. Runtimes using the
+/// systemd driver name it `<prefix>-<id>.scope`; with the cgroupfs driver
+/// it's the bare ID below a runtime directory like `docker`.
+fn container_id(component: &str, parent: &str) -> Option<(&'static str, String)> {
+    const PREFIXES: [(&str, &str); 4] = [
+        ("docker-", "docker"),
+        ("libpod-", "podman"),
+        ("cri-containerd-", "containerd"),
+        ("crio-", "cri-o"),
+    ];
+
+    if let Some(name) = component.strip_prefix("lxc.payload.") {
+        return Some(("lxc", name.to_string()));
+    }
+    let short = |id: &str| {
+        (id.len() >= 12 && id.chars().all(|c| c.is_ascii_hexdigit())).then(|| id[..12].to_string())
+    };
+    if let Some(scope) = component.strip_suffix(".scope") {
+        for (prefix, runtime) in PREFIXES {
+            if let Some(id) = scope.strip_prefix(prefix).and_then(short) {
+                return Some((runtime, id));
+            }
+        }
+    }
+    let id = short(component)?;
+    let runtime = match parent {
+        "docker" => "docker",
+        "libpod_parent" | "machine.slice" => "podman",
+        _ => "container",
+    };
+    Some((runtime, id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cpu_list(content: &str) -> Option<usize> {
+        let path = env::temp_dir().join(format!("cpu-list-test-{}", std::process::id()));
+        fs::write(&path, content).unwrap();
+        let count = read_cpu_list(&path);
+        fs::remove_file(&path).unwrap();
+        count
+    }
+
+    #[test]
+    fn counts_cpu_lists() {
+        assert_eq!(cpu_list("0\n"), Some(1));
+        assert_eq!(cpu_list("0-3\n"), Some(4));
+        assert_eq!(cpu_list("0-3,6,8-9\n"), Some(7));
+    }
+
+    #[test]
+    fn rejects_bad_cpu_lists() {
+        assert_eq!(cpu_list("\n"), None);
+        assert_eq!(cpu_list("3-1"), None);
+        assert_eq!(cpu_list("0-x"), None);
+        assert_eq!(cpu_list("all"), None);
+        assert_eq!(read_cpu_list(Path::new("/nonexistent/cpuset.cpus")), None);
+    }
+
+    #[test]
+    fn finds_container_ids() {
+        let id = "4f3c2b1a0e9d8c7b6a5f4e3d2c1b0a9f8e7d6c5b4a3f2e1d0c9b8a7f6e5d4c3b";
+        assert_eq!(
+            container_id(&format!("docker-{}.scope", id), "system.slice"),
+            Some(("docker", "4f3c2b1a0e9d".to_string()))
+        );
+        assert_eq!(
+            container_id(&format!("libpod-{}.scope", id), "machine.slice"),
+            Some(("podman", "4f3c2b1a0e9d".to_string()))
+        );
+        assert_eq!(
+            container_id(id, "docker"),
+            Some(("docker", "4f3c2b1a0e9d".to_string()))
+        );
+        assert_eq!(
+            container_id(id, "kubepods"),
+            Some(("container", "4f3c2b1a0e9d".to_string()))
+        );
+        assert_eq!(
+            container_id("lxc.payload.web", "lxc"),
+            Some(("lxc", "web".to_string()))
+        );
+    }
+
+    #[test]
+    fn ignores_other_cgroups() {
+        assert_eq!(container_id("nginx.service", "system.slice"), None);
+        assert_eq!(container_id("docker-abc.scope", "system.slice"), None);
+        assert_eq!(container_id("session-2.scope", "user-1000.slice"), None);
+        assert_eq!(container_id("0123456789abcdefxyz", "docker"), None);
+    }
+}
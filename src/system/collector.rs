@@ -0,0 +1,388 @@
+use super::cgroups::{CgroupMonitor, CgroupStats, ContainerLimits};
+use super::monitor::{self, CpuStats, DiskStats, MemoryStats, ProcessStats, SensorStats};
+use super::network::{NetworkSnapshot, NetworkStats};
+use super::services::{ServiceMonitor, ServiceStats};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use sysinfo::{Components, Disks, ProcessesToUpdate, System};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// One source of metrics, sampled on its own thread so a slow one (a hung
+/// network mount, a sensor driver that blocks) can't hold up the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Collector {
+    Cpu,
+    Memory,
+    Disks,
+    Network,
+    Processes,
+    Sensors,
+    Cgroups,
+    Services,
+}
+
+impl Collector {
+    pub const ALL: [Collector; 8] = [
+        Collector::Cpu,
+        Collector::Memory,
+        Collector::Disks,
+        Collector::Network,
+        Collector::Processes,
+        Collector::Sensors,
+        Collector::Cgroups,
+        Collector::Services,
+    ];
+
+    /// The collector's key in the `[intervals]` config section.
+    pub fn name(self) -> &'static str {
+        match self {
+            Collector::Cpu => "cpu",
+            Collector::Memory => "memory",
+            Collector::Disks => "disks",
+            Collector::Network => "network",
+            Collector::Processes => "processes",
+            Collector::Sensors => "sensors",
+            Collector::Cgroups => "cgroups",
+            Collector::Services => "services",
+        }
+    }
+
+    /// How long this collector can take to report before it's shown as stale.
+    fn timeout(self) -> Duration {
+        match self {
+            Collector::Cpu | Collector::Memory | Collector::Network => Duration::from_millis(500),
+            Collector::Disks | Collector::Sensors | Collector::Cgroups => Duration::from_secs(1),
+            Collector::Processes | Collector::Services => Duration::from_secs(2),
+        }
+    }
+}
+
+/// Puts CPU and memory readings in terms of the container the monitor runs
+/// in, unless host values were asked for.
+#[derive(Clone)]
+struct ContainerScope {
+    limits: ContainerLimits,
+    host_values: Arc<AtomicBool>,
+    /// The container's CPU time as of the last reading, and when.
+    previous: Option<(u64, Instant)>,
+}
+
+impl ContainerScope {
+    fn cpu(&mut self, cpu: &mut CpuStats) {
+        let now = Instant::now();
+        let Some(usec) = self.limits.cpu_usec() else {
+            return;
+        };
+        // Kept up while host values are shown, so switching back is immediate.
+        let previous = self.previous.replace((usec, now));
+        if self.host_values.load(Ordering::Relaxed) {
+            return;
+        }
+        let Some((before, at)) = previous else {
+            return;
+        };
+        let cpus = self.limits.cpus.unwrap_or(cpu.per_core.len() as f64);
+        let elapsed = now.duration_since(at).as_micros() as f64;
+        if cpus > 0.0 && elapsed > 0.0 {
+            let used = usec.saturating_sub(before) as f64 / elapsed / cpus * 100.0;
+            cpu.global_usage = used.min(100.0) as f32;
+        }
+    }
+
+    fn memory(&self, memory: &mut MemoryStats) {
+        if self.host_values.load(Ordering::Relaxed) {
+            return;
+        }
+        let Some(used) = self.limits.memory_current() else {
+            return;
+        };
+        memory.used = used;
+        memory.total = self.limits.memory_max.unwrap_or(memory.total);
+        if let Some(swap) = self.limits.swap_current() {
+            memory.swap_used = swap;
+            memory.swap_total = self.limits.swap_max.unwrap_or(memory.swap_total);
+        }
+    }
+}
+
+/// Owns the sysinfo state a collector samples from.
+enum Source {
+    Cpu(System, Option<ContainerScope>),
+    Memory(System, Option<ContainerScope>),
+    Disks(Disks),
+    Network(NetworkStats),
+    Processes(System),
+    Sensors(Components),
+    Cgroups(CgroupMonitor),
+    Services(ServiceMonitor),
+}
+
+impl Source {
+    fn new(collector: Collector, container: Option<ContainerScope>) -> Self {
+        match collector {
+            // CPU usage is measured between refreshes, so take the first one now.
+            Collector::Cpu => {
+                let mut system = System::new();
+                system.refresh_cpu_all();
+                let container = container.map(|mut container| {
+                    container.previous = container
+                        .limits
+                        .cpu_usec()
+                        .map(|usec| (usec, Instant::now()));
+                    container
+                });
+                Source::Cpu(system, container)
+            }
+            Collector::Memory => Source::Memory(System::new(), container),
+            Collector::Disks => Source::Disks(Disks::new_with_refreshed_list()),
+            Collector::Network => Source::Network(NetworkStats::new()),
+            Collector::Processes => {
+                let mut system = System::new();
+                system.refresh_processes(ProcessesToUpdate::All);
+                Source::Processes(system)
+            }
+            Collector::Sensors => Source::Sensors(Components::new_with_refreshed_list()),
+            Collector::Cgroups => Source::Cgroups(CgroupMonitor::new()),
+            Collector::Services => Source::Services(ServiceMonitor::new()),
+        }
+    }
+
+    fn collect(&mut self) -> Reading {
+        match self {
+            Source::Cpu(system, container) => {
+                let mut cpu = monitor::collect_cpu(system);
+                if let Some(container) = container {
+                    container.cpu(&mut cpu);
+                }
+                Reading::Cpu(cpu)
+            }
+            Source::Memory(system, container) => {
+                let mut memory = monitor::collect_memory(system);
+                if let Some(container) = container {
+                    container.memory(&mut memory);
+                }
+                Reading::Memory(memory)
+            }
+            Source::Disks(disks) => Reading::Disks(monitor::collect_disks(disks)),
+            Source::Network(network) => {
+                network.refresh();
+                Reading::Network(network.snapshot())
+            }
+            Source::Processes(system) => Reading::Processes(monitor::collect_processes(system)),
+            Source::Sensors(components) => Reading::Sensors(monitor::collect_sensors(components)),
+            Source::Cgroups(cgroups) => Reading::Cgroups(cgroups.collect()),
+            Source::Services(services) => Reading::Services(services.collect()),
+        }
+    }
+}
+
+/// What the collection thread waits on.
+enum Message {
+    /// The UI asking for these collectors to be sampled.
+    Request(Vec<Collector>),
+    /// A collector reporting back.
+    Reading(Collector, Reading),
+    /// The [`Collection`] handle was dropped.
+    Stop,
+}
+
+enum Reading {
+    Cpu(CpuStats),
+    Memory(MemoryStats),
+    Disks(Vec<DiskStats>),
+    Network(NetworkSnapshot),
+    Processes(Vec<ProcessStats>),
+    Sensors(Vec<SensorStats>),
+    Cgroups(Vec<CgroupStats>),
+    Services(Vec<ServiceStats>),
+}
+
+/// Everything the UI draws from, as of the latest reading.
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub cpu: CpuStats,
+    pub memory: MemoryStats,
+    pub disks: Vec<DiskStats>,
+    pub network: NetworkSnapshot,
+    pub processes: Vec<ProcessStats>,
+    pub sensors: Vec<SensorStats>,
+    pub cgroups: Vec<CgroupStats>,
+    pub services: Vec<ServiceStats>,
+    /// Collectors whose readings came in with this snapshot.
+    pub updated: BTreeSet<Collector>,
+    /// Collectors that are overdue, with when their data was last fresh.
+    pub stale: BTreeMap<Collector, Instant>,
+}
+
+impl Snapshot {
+    fn apply(&mut self, reading: Reading) {
+        match reading {
+            Reading::Cpu(cpu) => self.cpu = cpu,
+            Reading::Memory(memory) => self.memory = memory,
+            Reading::Disks(disks) => self.disks = disks,
+            Reading::Network(network) => self.network = network,
+            Reading::Processes(processes) => self.processes = processes,
+            Reading::Sensors(sensors) => self.sensors = sensors,
+            Reading::Cgroups(cgroups) => self.cgroups = cgroups,
+            Reading::Services(services) => self.services = services,
+        }
+    }
+
+    /// How long `collector`'s data has been behind, if it is.
+    pub fn stale_for(&self, collector: Collector) -> Option<Duration> {
+        self.stale.get(&collector).map(|since| since.elapsed())
+    }
+}
+
+/// Handle to the background collection thread. Dropping it stops the thread;
+/// collectors still sampling stop once their reading is done.
+pub struct Collection {
+    messages: Sender<Message>,
+    host_values: Arc<AtomicBool>,
+}
+
+impl Collection {
+    /// Starts collecting; each reading asked for with [`Collection::request`]
+    /// is published on the returned channel as soon as it comes in, so a slow
+    /// collector doesn't hold up the others. Inside a container, CPU and
+    /// memory are reported against its limits unless `host_values` is set.
+    pub fn spawn(
+        container: Option<ContainerLimits>,
+        host_values: bool,
+    ) -> (Self, UnboundedReceiver<Arc<Snapshot>>) {
+        let (messages, incoming) = mpsc::channel();
+        let (publish, snapshots) = unbounded_channel();
+        let host_values = Arc::new(AtomicBool::new(host_values));
+        let container = container
+            .filter(ContainerLimits::readable)
+            .map(|limits| ContainerScope {
+                limits,
+                host_values: host_values.clone(),
+                previous: None,
+            });
+        let results = messages.clone();
+        thread::Builder::new()
+            .name("collector".into())
+            .spawn(move || run(incoming, results, publish, container))
+            .expect("failed to spawn the collection thread");
+        (
+            Collection {
+                messages,
+                host_values,
+            },
+            snapshots,
+        )
+    }
+
+    /// Switches between container and host-wide CPU and memory figures.
+    pub fn set_host_values(&self, host_values: bool) {
+        self.host_values.store(host_values, Ordering::Relaxed);
+    }
+
+    /// Asks for a pass sampling `collectors`.
+    pub fn request(&self, collectors: Vec<Collector>) {
+        if !collectors.is_empty() {
+            let _ = self.messages.send(Message::Request(collectors));
+        }
+    }
+}
+
+impl Drop for Collection {
+    fn drop(&mut self) {
+        let _ = self.messages.send(Message::Stop);
+    }
+}
+
+fn run(
+    messages: Receiver<Message>,
+    results: Sender<Message>,
+    publish: UnboundedSender<Arc<Snapshot>>,
+    container: Option<ContainerScope>,
+) {
+    let workers: BTreeMap<Collector, Sender<()>> = Collector::ALL
+        .into_iter()
+        .map(|collector| {
+            (
+                collector,
+                spawn_worker(collector, results.clone(), container.clone()),
+            )
+        })
+        .collect();
+
+    let started = Instant::now();
+    let mut snapshot = Snapshot::default();
+    // Collectors working on a request, with when they were asked.
+    let mut busy: BTreeMap<Collector, Instant> = BTreeMap::new();
+    let mut fresh_at: BTreeMap<Collector, Instant> =
+        Collector::ALL.into_iter().map(|c| (c, started)).collect();
+
+    for message in messages {
+        match message {
+            Message::Request(collectors) => {
+                let now = Instant::now();
+                for collector in collectors {
+                    // A collector still working on an earlier request isn't asked again.
+                    if !busy.contains_key(&collector) && workers[&collector].send(()).is_ok() {
+                        busy.insert(collector, now);
+                    }
+                }
+                // Nothing new to show unless a collector has fallen behind.
+                let stale = overdue(&busy, &fresh_at);
+                if stale == snapshot.stale {
+                    continue;
+                }
+                snapshot.updated.clear();
+                snapshot.stale = stale;
+            }
+            Message::Reading(collector, reading) => {
+                busy.remove(&collector);
+                fresh_at.insert(collector, Instant::now());
+                snapshot.updated.clear();
+                snapshot.updated.insert(collector);
+                snapshot.apply(reading);
+                snapshot.stale = overdue(&busy, &fresh_at);
+            }
+            Message::Stop => break,
+        }
+        if publish.send(Arc::new(snapshot.clone())).is_err() {
+            break;
+        }
+    }
+}
+
+/// The busy collectors that have taken longer than their timeout, with when
+/// their data was last fresh.
+fn overdue(
+    busy: &BTreeMap<Collector, Instant>,
+    fresh_at: &BTreeMap<Collector, Instant>,
+) -> BTreeMap<Collector, Instant> {
+    busy.iter()
+        .filter(|(c, asked)| asked.elapsed() > c.timeout())
+        .map(|(c, _)| (*c, fresh_at[c]))
+        .collect()
+}
+
+fn spawn_worker(
+    collector: Collector,
+    results: Sender<Message>,
+    container: Option<ContainerScope>,
+) -> Sender<()> {
+    let (requests, incoming) = mpsc::channel::<()>();
+    thread::Builder::new()
+        .name(format!("collector-{:?}", collector).to_lowercase())
+        .spawn(move || {
+            let mut source = Source::new(collector, container);
+            for () in incoming {
+                let reading = source.collect();
+                if results.send(Message::Reading(collector, reading)).is_err() {
+                    break;
+                }
+            }
+        })
+        .expect("failed to spawn a collector thread");
+    requests
+}
@@ -0,0 +1,331 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const MAGIC: &[u8; 4] = b"RTMH";
+const FORMAT_VERSION: u8 = 1;
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+// (bucket size in seconds, number of buckets kept)
+// 1s for an hour, 1m for a day, 1h for a month.
+const TIERS: [(u64, usize); 3] = [(1, 3_600), (60, 1_440), (3_600, 720)];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    pub timestamp: u64,
+    pub value: f32,
+}
+
+#[derive(Debug, Clone)]
+struct Tier {
+    step: u64,
+    capacity: usize,
+    samples: VecDeque<Sample>,
+    bucket_start: u64,
+    sum: f64,
+    count: u32,
+}
+
+impl Tier {
+    fn new(step: u64, capacity: usize) -> Self {
+        Self {
+            step,
+            capacity,
+            samples: VecDeque::with_capacity(capacity),
+            bucket_start: 0,
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn record(&mut self, timestamp: u64, value: f32) {
+        let bucket = timestamp - timestamp % self.step;
+        if self.count > 0 && bucket != self.bucket_start {
+            self.flush();
+        }
+        self.bucket_start = bucket;
+        self.sum += value as f64;
+        self.count += 1;
+    }
+
+    fn flush(&mut self) {
+        if self.count == 0 {
+            return;
+        }
+        self.samples.push_back(Sample {
+            timestamp: self.bucket_start,
+            value: (self.sum / self.count as f64) as f32,
+        });
+        while self.samples.len() > self.capacity {
+            self.samples.pop_front();
+        }
+        self.sum = 0.0;
+        self.count = 0;
+    }
+
+    fn pending(&self) -> Option<Sample> {
+        (self.count > 0).then(|| Sample {
+            timestamp: self.bucket_start,
+            value: (self.sum / self.count as f64) as f32,
+        })
+    }
+
+    fn retention(&self) -> u64 {
+        self.step * self.capacity as u64
+    }
+
+    fn prune(&mut self, now: u64) {
+        let cutoff = now.saturating_sub(self.retention());
+        while self.samples.front().is_some_and(|s| s.timestamp < cutoff) {
+            self.samples.pop_front();
+        }
+        if self.count > 0 && self.bucket_start < cutoff {
+            self.sum = 0.0;
+            self.count = 0;
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = Sample> + '_ {
+        self.samples.iter().copied().chain(self.pending())
+    }
+}
+
+/// A single metric downsampled into fixed retention tiers.
+#[derive(Debug, Clone)]
+pub struct TimeSeries {
+    tiers: Vec<Tier>,
+}
+
+impl TimeSeries {
+    pub fn new() -> Self {
+        Self {
+            tiers: TIERS
+                .iter()
+                .map(|&(step, capacity)| Tier::new(step, capacity))
+                .collect(),
+        }
+    }
+
+    pub fn record(&mut self, timestamp: u64, value: f32) {
+        for tier in &mut self.tiers {
+            tier.record(timestamp, value);
+        }
+    }
+
+    /// The newest sample across all tiers, if any has been recorded.
+    pub fn latest(&self) -> Option<Sample> {
+        self.tiers[0].iter().last()
+    }
+
+    /// The oldest sample still retained by any tier.
+    pub fn oldest(&self) -> Option<Sample> {
+        self.tiers
+            .iter()
+            .filter_map(|t| t.iter().next())
+            .min_by_key(|s| s.timestamp)
+    }
+
+    /// Samples in the `window` seconds ending at `end`, taken from the finest
+    /// tier that still retains that point in time.
+    pub fn range(&self, end: u64, window: u64) -> Vec<Sample> {
+        let tier = self.tier_for(end.saturating_sub(window));
+        let start = end.saturating_sub(window);
+        tier.iter()
+            .filter(|s| s.timestamp > start && s.timestamp <= end)
+            .collect()
+    }
+
+    fn tier_for(&self, start: u64) -> &Tier {
+        let newest = self.latest().map(|s| s.timestamp).unwrap_or_else(unix_now);
+        let age = newest.saturating_sub(start);
+        self.tiers
+            .iter()
+            .find(|t| t.retention() >= age)
+            .unwrap_or_else(|| self.tiers.last().unwrap())
+    }
+
+    fn prune(&mut self, now: u64) {
+        for tier in &mut self.tiers {
+            tier.prune(now);
+        }
+    }
+
+    fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+        for tier in &self.tiers {
+            let samples: Vec<Sample> = tier.iter().collect();
+            out.write_all(&(samples.len() as u32).to_le_bytes())?;
+            for sample in samples {
+                out.write_all(&sample.timestamp.to_le_bytes())?;
+                out.write_all(&sample.value.to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_from(input: &mut impl Read) -> io::Result<Self> {
+        let mut series = Self::new();
+        for tier in &mut series.tiers {
+            let count = read_u32(input)? as usize;
+            for _ in 0..count {
+                let timestamp = read_u64(input)?;
+                let value = read_f32(input)?;
+                tier.samples.push_back(Sample { timestamp, value });
+            }
+            while tier.samples.len() > tier.capacity {
+                tier.samples.pop_front();
+            }
+            // Resume the newest bucket from disk so it is not duplicated on the next sample.
+            if let Some(last) = tier.samples.pop_back() {
+                tier.bucket_start = last.timestamp;
+                tier.sum = last.value as f64;
+                tier.count = 1;
+            }
+        }
+        Ok(series)
+    }
+}
+
+/// Time series for every metric the monitor charts, optionally persisted to disk.
+pub struct MetricsHistory {
+    pub cpu: TimeSeries,
+    pub memory: TimeSeries,
+    pub swap: TimeSeries,
+    pub net_rx: TimeSeries,
+    pub net_tx: TimeSeries,
+    path: Option<PathBuf>,
+    last_save: Instant,
+}
+
+impl MetricsHistory {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        let mut history = Self {
+            cpu: TimeSeries::new(),
+            memory: TimeSeries::new(),
+            swap: TimeSeries::new(),
+            net_rx: TimeSeries::new(),
+            net_tx: TimeSeries::new(),
+            path,
+            last_save: Instant::now(),
+        };
+
+        if let Some(path) = history.path.clone() {
+            // A missing or corrupt store just means we start with an empty history.
+            let _ = history.load(&path);
+        }
+
+        history
+    }
+
+    pub fn record(&mut self, cpu: f32, memory: f32, swap: f32, net_rx: f64, net_tx: f64) {
+        let now = unix_now();
+        self.cpu.record(now, cpu);
+        self.memory.record(now, memory);
+        self.swap.record(now, swap);
+        self.net_rx.record(now, net_rx as f32);
+        self.net_tx.record(now, net_tx as f32);
+
+        if self.last_save.elapsed() >= SAVE_INTERVAL {
+            let _ = self.save();
+        }
+    }
+
+    pub fn save(&mut self) -> io::Result<()> {
+        self.last_save = Instant::now();
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(MAGIC);
+        buf.push(FORMAT_VERSION);
+        for (name, series) in self.series() {
+            buf.push(name.len() as u8);
+            buf.extend_from_slice(name.as_bytes());
+            series.write_to(&mut buf)?;
+        }
+
+        // Write to a sibling file first so a crash mid-write never truncates the store.
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, &buf)?;
+        fs::rename(&tmp, path)
+    }
+
+    fn load(&mut self, path: &Path) -> io::Result<()> {
+        let data = fs::read(path)?;
+        let mut input = data.as_slice();
+
+        let mut magic = [0u8; 4];
+        input.read_exact(&mut magic)?;
+        let mut version = [0u8; 1];
+        input.read_exact(&mut version)?;
+        if &magic != MAGIC || version[0] != FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unrecognized history file",
+            ));
+        }
+
+        let now = unix_now();
+        while !input.is_empty() {
+            let mut len = [0u8; 1];
+            input.read_exact(&mut len)?;
+            let mut name = vec![0u8; len[0] as usize];
+            input.read_exact(&mut name)?;
+
+            let mut series = TimeSeries::read_from(&mut input)?;
+            series.prune(now);
+
+            match name.as_slice() {
+                b"cpu" => self.cpu = series,
+                b"memory" => self.memory = series,
+                b"swap" => self.swap = series,
+                b"net_rx" => self.net_rx = series,
+                b"net_tx" => self.net_tx = series,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn series(&self) -> [(&'static str, &TimeSeries); 5] {
+        [
+            ("cpu", &self.cpu),
+            ("memory", &self.memory),
+            ("swap", &self.swap),
+            ("net_rx", &self.net_rx),
+            ("net_tx", &self.net_tx),
+        ]
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn read_u32(input: &mut impl Read) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    input.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64(input: &mut impl Read) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    input.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_f32(input: &mut impl Read) -> io::Result<f32> {
+    let mut buf = [0u8; 4];
+    input.read_exact(&mut buf)?;
+    Ok(f32::from_le_bytes(buf))
+}
@@ -1,8 +1,14 @@
-pub mod info;
-pub mod monitor;
-pub mod network;
-
-pub use info::SystemInfo;
-pub use monitor::SystemMonitor;
-pub use network::NetworkStats;
-
+pub mod cgroups;
+pub mod collector;
+pub mod history;
+pub mod info;
+pub mod monitor;
+pub mod network;
+pub mod scheduler;
+pub mod services;
+
+pub use collector::{Collection, Collector, Snapshot};
+pub use history::MetricsHistory;
+pub use info::SystemInfo;
+pub use network::NetworkStats;
+pub use scheduler::{IntervalsConfig, Scheduler};
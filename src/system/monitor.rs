@@ -1,173 +1,276 @@
-use sysinfo::{Disks, System};
-use std::collections::VecDeque;
-
-const HISTORY_SIZE: usize = 60;
-
-#[derive(Debug, Clone)]
-pub struct CpuStats {
-    pub global_usage: f32,
-    #[allow(dead_code)]
-    pub per_core: Vec<f32>,
-}
-
-#[derive(Debug, Clone)]
-pub struct MemoryStats {
-    pub used: u64,
-    pub total: u64,
-    pub swap_used: u64,
-    pub swap_total: u64,
-}
-
-impl MemoryStats {
-    pub fn used_percent(&self) -> f32 {
-        if self.total == 0 {
-            0.0
-        } else {
-            (self.used as f32 / self.total as f32) * 100.0
-        }
-    }
-
-    pub fn swap_percent(&self) -> f32 {
-        if self.swap_total == 0 {
-            0.0
-        } else {
-            (self.swap_used as f32 / self.swap_total as f32) * 100.0
-        }
-    }
-
-    pub fn used_gb(&self) -> f64 {
-        self.used as f64 / 1024.0 / 1024.0 / 1024.0
-    }
-
-    pub fn total_gb(&self) -> f64 {
-        self.total as f64 / 1024.0 / 1024.0 / 1024.0
-    }
-
-    pub fn swap_used_gb(&self) -> f64 {
-        self.swap_used as f64 / 1024.0 / 1024.0 / 1024.0
-    }
-
-    pub fn swap_total_gb(&self) -> f64 {
-        self.swap_total as f64 / 1024.0 / 1024.0 / 1024.0
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct DiskStats {
-    pub name: String,
-    #[allow(dead_code)]
-    pub mount_point: String,
-    pub total: u64,
-    pub available: u64,
-    pub disk_type: String,
-    #[allow(dead_code)]
-    pub file_system: String,
-}
-
-impl DiskStats {
-    pub fn used(&self) -> u64 {
-        self.total.saturating_sub(self.available)
-    }
-
-    pub fn used_percent(&self) -> f32 {
-        if self.total == 0 {
-            0.0
-        } else {
-            (self.used() as f32 / self.total as f32) * 100.0
-        }
-    }
-
-    pub fn total_gb(&self) -> f64 {
-        self.total as f64 / 1024.0 / 1024.0 / 1024.0
-    }
-
-    pub fn used_gb(&self) -> f64 {
-        self.used() as f64 / 1024.0 / 1024.0 / 1024.0
-    }
-
-    #[allow(dead_code)]
-    pub fn available_gb(&self) -> f64 {
-        self.available as f64 / 1024.0 / 1024.0 / 1024.0
-    }
-}
-
-pub struct SystemMonitor {
-    system: System,
-    disks: Disks,
-    cpu_history: VecDeque<f32>,
-}
-
-impl SystemMonitor {
-    pub fn new() -> Self {
-        let mut system = System::new_all();
-        system.refresh_all();
-
-        Self {
-            system,
-            disks: Disks::new_with_refreshed_list(),
-            cpu_history: VecDeque::with_capacity(HISTORY_SIZE),
-        }
-    }
-
-    pub fn refresh(&mut self) {
-        self.system.refresh_cpu_all();
-        self.system.refresh_memory();
-        self.disks.refresh();
-    }
-
-    pub fn cpu_stats(&self) -> CpuStats {
-        let global_usage = self.system.global_cpu_usage();
-        let per_core = self.system.cpus().iter().map(|cpu| cpu.cpu_usage()).collect();
-
-        CpuStats {
-            global_usage,
-            per_core,
-        }
-    }
-
-    pub fn update_cpu_history(&mut self) {
-        let global_usage = self.system.global_cpu_usage();
-        self.cpu_history.push_back(global_usage);
-        if self.cpu_history.len() > HISTORY_SIZE {
-            self.cpu_history.pop_front();
-        }
-    }
-
-    pub fn cpu_history(&self) -> Vec<f32> {
-        self.cpu_history.iter().copied().collect()
-    }
-
-    pub fn memory_stats(&self) -> MemoryStats {
-        MemoryStats {
-            used: self.system.used_memory(),
-            total: self.system.total_memory(),
-            swap_used: self.system.used_swap(),
-            swap_total: self.system.total_swap(),
-        }
-    }
-
-    pub fn disk_stats(&self) -> Vec<DiskStats> {
-        self.disks
-            .iter()
-            .map(|disk| {
-                let disk_type = match disk.kind() {
-                    sysinfo::DiskKind::HDD => "HDD",
-                    sysinfo::DiskKind::SSD => "SSD",
-                    _ => "Unknown",
-                }.to_string();
-
-                let file_system = disk.file_system().to_string_lossy().to_string();
-
-                DiskStats {
-                    name: disk.name().to_string_lossy().to_string(),
-                    mount_point: disk.mount_point().to_string_lossy().to_string(),
-                    total: disk.total_space(),
-                    available: disk.available_space(),
-                    disk_type,
-                    file_system,
-                }
-            })
-            .collect()
-    }
-}
-
+use sysinfo::{Components, Disks, ProcessesToUpdate, System};
+
+#[derive(Debug, Clone, Default)]
+pub struct CpuStats {
+    pub global_usage: f32,
+    pub per_core: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStats {
+    pub used: u64,
+    pub total: u64,
+    pub swap_used: u64,
+    pub swap_total: u64,
+}
+
+impl MemoryStats {
+    pub fn used_percent(&self) -> f32 {
+        if self.total == 0 {
+            0.0
+        } else {
+            (self.used as f32 / self.total as f32) * 100.0
+        }
+    }
+
+    pub fn swap_percent(&self) -> f32 {
+        if self.swap_total == 0 {
+            0.0
+        } else {
+            (self.swap_used as f32 / self.swap_total as f32) * 100.0
+        }
+    }
+
+    pub fn used_gb(&self) -> f64 {
+        self.used as f64 / 1024.0 / 1024.0 / 1024.0
+    }
+
+    pub fn total_gb(&self) -> f64 {
+        self.total as f64 / 1024.0 / 1024.0 / 1024.0
+    }
+
+    pub fn swap_used_gb(&self) -> f64 {
+        self.swap_used as f64 / 1024.0 / 1024.0 / 1024.0
+    }
+
+    pub fn swap_total_gb(&self) -> f64 {
+        self.swap_total as f64 / 1024.0 / 1024.0 / 1024.0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiskStats {
+    pub name: String,
+    pub mount_point: String,
+    pub total: u64,
+    pub available: u64,
+    pub disk_type: String,
+    pub file_system: String,
+}
+
+impl DiskStats {
+    pub fn used(&self) -> u64 {
+        self.total.saturating_sub(self.available)
+    }
+
+    pub fn used_percent(&self) -> f32 {
+        if self.total == 0 {
+            0.0
+        } else {
+            (self.used() as f32 / self.total as f32) * 100.0
+        }
+    }
+
+    pub fn total_gb(&self) -> f64 {
+        self.total as f64 / 1024.0 / 1024.0 / 1024.0
+    }
+
+    pub fn used_gb(&self) -> f64 {
+        self.used() as f64 / 1024.0 / 1024.0 / 1024.0
+    }
+
+    pub fn available_gb(&self) -> f64 {
+        self.available as f64 / 1024.0 / 1024.0 / 1024.0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProcessStats {
+    pub pid: u32,
+    pub name: String,
+    /// Full command line, or the name when it can't be read.
+    pub command: String,
+    pub cpu_usage: f32,
+    pub memory: u64,
+    pub status: String,
+    pub run_time: u64,
+}
+
+/// A column of the process table that it can be sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessColumn {
+    Pid,
+    Name,
+    Cpu,
+    Memory,
+    Status,
+    Time,
+}
+
+impl ProcessColumn {
+    /// Numbers read best largest first, text alphabetically.
+    fn descending_by_default(self) -> bool {
+        matches!(self, Self::Cpu | Self::Memory | Self::Time)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessSort {
+    pub column: ProcessColumn,
+    pub descending: bool,
+}
+
+impl Default for ProcessSort {
+    fn default() -> Self {
+        Self {
+            column: ProcessColumn::Cpu,
+            descending: true,
+        }
+    }
+}
+
+impl ProcessSort {
+    /// Sorting by the current column again flips the direction.
+    pub fn by(self, column: ProcessColumn) -> Self {
+        if column == self.column {
+            Self {
+                column,
+                descending: !self.descending,
+            }
+        } else {
+            Self {
+                column,
+                descending: column.descending_by_default(),
+            }
+        }
+    }
+
+    pub fn apply(self, processes: &mut [ProcessStats]) {
+        processes.sort_by(|a, b| {
+            let ordering = match self.column {
+                ProcessColumn::Pid => a.pid.cmp(&b.pid),
+                ProcessColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                ProcessColumn::Cpu => a.cpu_usage.total_cmp(&b.cpu_usage),
+                ProcessColumn::Memory => a.memory.cmp(&b.memory),
+                ProcessColumn::Status => a.status.cmp(&b.status),
+                ProcessColumn::Time => a.run_time.cmp(&b.run_time),
+            };
+            if self.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+}
+
+impl ProcessStats {
+    pub fn memory_mb(&self) -> f64 {
+        self.memory as f64 / 1024.0 / 1024.0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SensorStats {
+    pub label: String,
+    /// Degrees Celsius.
+    pub temperature: f32,
+    pub max: f32,
+    pub critical: Option<f32>,
+}
+
+pub fn collect_cpu(system: &mut System) -> CpuStats {
+    system.refresh_cpu_all();
+    let global_usage = system.global_cpu_usage();
+    let per_core = system.cpus().iter().map(|cpu| cpu.cpu_usage()).collect();
+
+    CpuStats {
+        global_usage,
+        per_core,
+    }
+}
+
+pub fn collect_memory(system: &mut System) -> MemoryStats {
+    system.refresh_memory();
+    MemoryStats {
+        used: system.used_memory(),
+        total: system.total_memory(),
+        swap_used: system.used_swap(),
+        swap_total: system.total_swap(),
+    }
+}
+
+pub fn collect_disks(disks: &mut Disks) -> Vec<DiskStats> {
+    disks.refresh();
+    disks
+        .iter()
+        .map(|disk| {
+            let disk_type = match disk.kind() {
+                sysinfo::DiskKind::HDD => "HDD",
+                sysinfo::DiskKind::SSD => "SSD",
+                _ => "Unknown",
+            }
+            .to_string();
+
+            let file_system = disk.file_system().to_string_lossy().to_string();
+
+            DiskStats {
+                name: disk.name().to_string_lossy().to_string(),
+                mount_point: disk.mount_point().to_string_lossy().to_string(),
+                total: disk.total_space(),
+                available: disk.available_space(),
+                disk_type,
+                file_system,
+            }
+        })
+        .collect()
+}
+
+/// Running processes, busiest first.
+pub fn collect_processes(system: &mut System) -> Vec<ProcessStats> {
+    system.refresh_processes(ProcessesToUpdate::All);
+    let mut processes: Vec<ProcessStats> = system
+        .processes()
+        .values()
+        // On Linux sysinfo also lists each process's threads; show processes only.
+        .filter(|process| process.thread_kind().is_none())
+        .map(|process| {
+            let name = process.name().to_string_lossy().to_string();
+            let command = process
+                .cmd()
+                .iter()
+                .map(|arg| arg.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(" ");
+            ProcessStats {
+                pid: process.pid().as_u32(),
+                command: if command.is_empty() {
+                    name.clone()
+                } else {
+                    command
+                },
+                name,
+                cpu_usage: process.cpu_usage(),
+                memory: process.memory(),
+                status: process.status().to_string(),
+                run_time: process.run_time(),
+            }
+        })
+        .collect();
+
+    processes.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+    processes
+}
+
+pub fn collect_sensors(components: &mut Components) -> Vec<SensorStats> {
+    components.refresh();
+    components
+        .iter()
+        .map(|component| SensorStats {
+            label: component.label().to_string(),
+            temperature: component.temperature(),
+            max: component.max(),
+            critical: component.critical(),
+        })
+        .collect()
+}
@@ -0,0 +1,129 @@
+use super::collector::Collector;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// Shortest interval a collector can be sampled at, in milliseconds.
+pub const MIN_INTERVAL: u64 = 100;
+
+/// Per-collector sample intervals in milliseconds. Collectors left out are
+/// sampled every `refresh_rate`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IntervalsConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disks: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub processes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sensors: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cgroups: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub services: Option<u64>,
+}
+
+impl IntervalsConfig {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn get(&self, collector: Collector) -> Option<u64> {
+        match collector {
+            Collector::Cpu => self.cpu,
+            Collector::Memory => self.memory,
+            Collector::Disks => self.disks,
+            Collector::Network => self.network,
+            Collector::Processes => self.processes,
+            Collector::Sensors => self.sensors,
+            Collector::Cgroups => self.cgroups,
+            Collector::Services => self.services,
+        }
+    }
+}
+
+/// Decides which collectors are due for a sample, each on its own interval.
+#[derive(Debug)]
+pub struct Scheduler {
+    intervals: BTreeMap<Collector, Duration>,
+    due: BTreeMap<Collector, Instant>,
+}
+
+impl Scheduler {
+    /// Every collector starts out due, so the first pass samples everything.
+    pub fn new(intervals: &IntervalsConfig, refresh_rate: u64) -> Self {
+        let now = Instant::now();
+        Self {
+            intervals: resolve(intervals, refresh_rate),
+            due: Collector::ALL.into_iter().map(|c| (c, now)).collect(),
+        }
+    }
+
+    /// Switches to new intervals, bringing forward any sample that would now
+    /// come later than one new interval from `now`.
+    pub fn set_intervals(&mut self, intervals: &IntervalsConfig, refresh_rate: u64, now: Instant) {
+        self.intervals = resolve(intervals, refresh_rate);
+        for (collector, at) in &mut self.due {
+            *at = (*at).min(now + self.intervals[collector]);
+        }
+    }
+
+    pub fn interval(&self, collector: Collector) -> Duration {
+        self.intervals[&collector]
+    }
+
+    /// When the next collector falls due.
+    pub fn next_due(&self) -> Instant {
+        self.due
+            .values()
+            .min()
+            .copied()
+            .unwrap_or_else(Instant::now)
+    }
+
+    /// The collectors due at `now`, each rescheduled one interval on. One
+    /// that fell more than an interval behind restarts from `now` instead of
+    /// firing repeatedly to catch up.
+    pub fn take_due(&mut self, now: Instant) -> Vec<Collector> {
+        let mut due = Vec::new();
+        for (&collector, at) in &mut self.due {
+            if *at > now {
+                continue;
+            }
+            let interval = self.intervals[&collector];
+            *at += interval;
+            if *at <= now {
+                *at = now + interval;
+            }
+            due.push(collector);
+        }
+        due
+    }
+
+    /// Marks every collector as sampled at `now`, for a manual refresh.
+    pub fn restart(&mut self, now: Instant) -> Vec<Collector> {
+        for (collector, at) in &mut self.due {
+            *at = now + self.intervals[collector];
+        }
+        Collector::ALL.to_vec()
+    }
+}
+
+fn resolve(intervals: &IntervalsConfig, refresh_rate: u64) -> BTreeMap<Collector, Duration> {
+    Collector::ALL
+        .into_iter()
+        .map(|collector| {
+            let ms = intervals
+                .get(collector)
+                .unwrap_or(refresh_rate)
+                .max(MIN_INTERVAL);
+            (collector, Duration::from_millis(ms))
+        })
+        .collect()
+}
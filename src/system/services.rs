@@ -0,0 +1,230 @@
+use super::cgroups::{read_keyed, read_value, CGROUP_ROOT};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::Instant;
+
+/// Exists while systemd is the init system, the check `sd_booted` makes.
+const SYSTEMD_RUNTIME: &str = "/run/systemd/system";
+
+/// The slice systemd runs system services in.
+const SYSTEM_SLICE: &str = "system.slice";
+
+#[derive(Debug, Clone)]
+pub struct ServiceStats {
+    /// The unit name, such as `nginx.service`.
+    pub unit: String,
+    pub description: String,
+    /// `active`, `failed`, `activating` and so on.
+    pub active_state: String,
+    /// The finer state next to it, such as `running` or `exited`; empty when
+    /// only the cgroup was read.
+    pub sub_state: String,
+    pub main_pid: Option<u32>,
+    /// Percent of one CPU, like process CPU usage.
+    pub cpu_usage: f32,
+    /// `None` for services without a cgroup, which have no processes.
+    pub memory: Option<u64>,
+}
+
+impl ServiceStats {
+    pub fn failed(&self) -> bool {
+        self.active_state == "failed"
+    }
+}
+
+/// A service's cgroup as of one pass.
+struct Usage {
+    cpu_usec: u64,
+    memory: u64,
+}
+
+/// Lists systemd services by joining unit states from `systemctl` with the
+/// CPU and memory use of their cgroups under `system.slice`. Without
+/// `systemctl` the cgroups alone are read, which only finds running services.
+pub struct ServiceMonitor {
+    previous: HashMap<String, u64>,
+    last_update: Instant,
+}
+
+impl ServiceMonitor {
+    pub fn new() -> Self {
+        Self {
+            previous: HashMap::new(),
+            last_update: Instant::now(),
+        }
+    }
+
+    /// Whether systemd is the init system, so `systemctl` can answer.
+    pub fn systemd_running() -> bool {
+        Path::new(SYSTEMD_RUNTIME).is_dir()
+    }
+
+    /// Whether there's a `system.slice` cgroup to read services from.
+    pub fn cgroups_available() -> bool {
+        Path::new(CGROUP_ROOT).join(SYSTEM_SLICE).is_dir()
+    }
+
+    /// Loaded services that are running, starting or stopping, or failed:
+    /// failed ones first, then by name.
+    pub fn collect(&mut self) -> Vec<ServiceStats> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_update).as_secs_f64();
+        self.last_update = now;
+
+        let mut usage = HashMap::new();
+        read_slice(&Path::new(CGROUP_ROOT).join(SYSTEM_SLICE), &mut usage);
+
+        let mut services = if Self::systemd_running() {
+            list_units().unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        if services.is_empty() {
+            // No systemctl to ask, so every service with a cgroup is taken
+            // to be running.
+            services = usage
+                .keys()
+                .map(|unit| ServiceStats {
+                    unit: unit.clone(),
+                    description: String::new(),
+                    active_state: "active".to_string(),
+                    sub_state: String::new(),
+                    main_pid: None,
+                    cpu_usage: 0.0,
+                    memory: None,
+                })
+                .collect();
+        } else {
+            let units: Vec<&str> = services
+                .iter()
+                .map(|service| service.unit.as_str())
+                .collect();
+            let pids = main_pids(&units);
+            for service in &mut services {
+                service.main_pid = pids.get(&service.unit).copied();
+            }
+        }
+
+        let mut counters = HashMap::new();
+        for service in &mut services {
+            let Some(usage) = usage.get(&service.unit) else {
+                continue;
+            };
+            if let Some(before) = self.previous.get(&service.unit) {
+                if elapsed > 0.0 {
+                    let used = usage.cpu_usec.saturating_sub(*before) as f64 / elapsed;
+                    service.cpu_usage = (used / 10_000.0) as f32;
+                }
+            }
+            service.memory = Some(usage.memory);
+            counters.insert(service.unit.clone(), usage.cpu_usec);
+        }
+        self.previous = counters;
+
+        services.sort_by(|a, b| {
+            b.failed()
+                .cmp(&a.failed())
+                .then_with(|| a.unit.cmp(&b.unit))
+        });
+        services
+    }
+}
+
+/// The service cgroups in a slice, including those in slices below it such
+/// as `system-getty.slice`.
+fn read_slice(dir: &Path, usage: &mut HashMap<String, Usage>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        let path = entry.path();
+        if name.ends_with(".slice") {
+            read_slice(&path, usage);
+        } else if name.ends_with(".service") {
+            usage.insert(
+                name,
+                Usage {
+                    cpu_usec: read_keyed(&path.join("cpu.stat"), "usage_usec").unwrap_or(0),
+                    memory: read_value(&path.join("memory.current")).unwrap_or(0),
+                },
+            );
+        }
+    }
+}
+
+/// Services `systemctl` lists by default: those that are active, changing
+/// state, or failed. `None` when it can't be run.
+fn list_units() -> Option<Vec<ServiceStats>> {
+    let output = Command::new("systemctl")
+        .args([
+            "list-units",
+            "--type=service",
+            "--no-legend",
+            "--no-pager",
+            "--plain",
+        ])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let services = stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let unit = fields.next()?;
+            let load = fields.next()?;
+            let active = fields.next()?;
+            let sub = fields.next()?;
+            (load == "loaded").then(|| ServiceStats {
+                unit: unit.to_string(),
+                description: fields.collect::<Vec<_>>().join(" "),
+                active_state: active.to_string(),
+                sub_state: sub.to_string(),
+                main_pid: None,
+                cpu_usage: 0.0,
+                memory: None,
+            })
+        })
+        .collect();
+    Some(services)
+}
+
+/// The main PID of each of `units` that has one running.
+fn main_pids(units: &[&str]) -> HashMap<String, u32> {
+    let mut pids = HashMap::new();
+    if units.is_empty() {
+        return pids;
+    }
+    let Ok(output) = Command::new("systemctl")
+        .args(["show", "--property=Id,MainPID", "--no-pager"])
+        .args(units)
+        .output()
+    else {
+        return pids;
+    };
+
+    // One block of `key=value` lines per unit, separated by blank lines.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for block in stdout.split("\n\n") {
+        let mut id = None;
+        let mut pid = None;
+        for line in block.lines() {
+            match line.split_once('=') {
+                Some(("Id", value)) => id = Some(value.to_string()),
+                Some(("MainPID", value)) => pid = value.parse::<u32>().ok().filter(|pid| *pid > 0),
+                _ => {}
+            }
+        }
+        if let (Some(id), Some(pid)) = (id, pid) {
+            pids.insert(id, pid);
+        }
+    }
+    pids
+}
@@ -1,246 +1,1039 @@
-use ratatui::style::Color;
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Theme {
-    pub background: [u8; 3],
-    pub foreground: [u8; 3],
-    pub primary: [u8; 3],
-    pub secondary: [u8; 3],
-    pub success: [u8; 3],
-    pub warning: [u8; 3],
-    pub danger: [u8; 3],
-    pub border: [u8; 3],
-}
-
-impl Theme {
-    pub fn default() -> Self {
-        Self {
-            background: [26, 27, 38],
-            foreground: [198, 208, 245],
-            primary: [137, 180, 250],
-            secondary: [203, 166, 247],
-            success: [166, 227, 161],
-            warning: [249, 226, 175],
-            danger: [243, 139, 168],
-            border: [88, 91, 112],
-        }
-    }
-
-    pub fn nord() -> Self {
-        Self {
-            background: [46, 52, 64],
-            foreground: [236, 239, 244],
-            primary: [136, 192, 208],
-            secondary: [129, 161, 193],
-            success: [163, 190, 140],
-            warning: [235, 203, 139],
-            danger: [191, 97, 106],
-            border: [67, 76, 94],
-        }
-    }
-
-    pub fn gruvbox() -> Self {
-        Self {
-            background: [40, 40, 40],
-            foreground: [235, 219, 178],
-            primary: [131, 165, 152],
-            secondary: [211, 134, 155],
-            success: [184, 187, 38],
-            warning: [250, 189, 47],
-            danger: [251, 73, 52],
-            border: [102, 92, 84],
-        }
-    }
-
-    pub fn dracula() -> Self {
-        Self {
-            background: [40, 42, 54],
-            foreground: [248, 248, 242],
-            primary: [139, 233, 253],
-            secondary: [189, 147, 249],
-            success: [80, 250, 123],
-            warning: [241, 250, 140],
-            danger: [255, 85, 85],
-            border: [68, 71, 90],
-        }
-    }
-
-    pub fn monokai() -> Self {
-        Self {
-            background: [39, 40, 34],
-            foreground: [248, 248, 240],
-            primary: [102, 217, 239],
-            secondary: [174, 129, 255],
-            success: [166, 226, 46],
-            warning: [244, 191, 117],
-            danger: [249, 38, 114],
-            border: [73, 72, 62],
-        }
-    }
-
-    pub fn cyberpunk() -> Self {
-        Self {
-            background: [16, 18, 27],
-            foreground: [255, 0, 255],
-            primary: [0, 255, 255],
-            secondary: [255, 0, 128],
-            success: [0, 255, 0],
-            warning: [255, 255, 0],
-            danger: [255, 0, 100],
-            border: [138, 43, 226],
-        }
-    }
-
-    pub fn tokyo_night() -> Self {
-        Self {
-            background: [26, 27, 38],
-            foreground: [192, 202, 245],
-            primary: [122, 162, 247],
-            secondary: [187, 154, 247],
-            success: [158, 206, 106],
-            warning: [224, 175, 104],
-            danger: [247, 118, 142],
-            border: [65, 72, 104],
-        }
-    }
-
-    pub fn solarized_dark() -> Self {
-        Self {
-            background: [0, 43, 54],
-            foreground: [131, 148, 150],
-            primary: [38, 139, 210],
-            secondary: [108, 113, 196],
-            success: [133, 153, 0],
-            warning: [181, 137, 0],
-            danger: [220, 50, 47],
-            border: [7, 54, 66],
-        }
-    }
-
-    pub fn solarized_light() -> Self {
-        Self {
-            background: [253, 246, 227],
-            foreground: [101, 123, 131],
-            primary: [38, 139, 210],
-            secondary: [108, 113, 196],
-            success: [133, 153, 0],
-            warning: [181, 137, 0],
-            danger: [220, 50, 47],
-            border: [238, 232, 213],
-        }
-    }
-
-    pub fn one_dark() -> Self {
-        Self {
-            background: [40, 44, 52],
-            foreground: [171, 178, 191],
-            primary: [97, 175, 239],
-            secondary: [198, 120, 221],
-            success: [152, 195, 121],
-            warning: [229, 192, 123],
-            danger: [224, 108, 117],
-            border: [76, 82, 99],
-        }
-    }
-
-    pub fn material() -> Self {
-        Self {
-            background: [38, 50, 56],
-            foreground: [236, 239, 241],
-            primary: [128, 203, 196],
-            secondary: [255, 202, 40],
-            success: [195, 232, 141],
-            warning: [255, 213, 79],
-            danger: [239, 83, 80],
-            border: [69, 90, 100],
-        }
-    }
-
-    pub fn ayu_dark() -> Self {
-        Self {
-            background: [10, 14, 20],
-            foreground: [230, 237, 243],
-            primary: [89, 181, 249],
-            secondary: [223, 142, 255],
-            success: [186, 230, 126],
-            warning: [255, 180, 84],
-            danger: [242, 119, 122],
-            border: [15, 20, 25],
-        }
-    }
-
-    pub fn rosepine() -> Self {
-        Self {
-            background: [25, 23, 36],
-            foreground: [224, 222, 244],
-            primary: [156, 207, 216],
-            secondary: [196, 167, 231],
-            success: [49, 116, 143],
-            warning: [246, 193, 119],
-            danger: [235, 111, 146],
-            border: [33, 32, 46],
-        }
-    }
-
-    pub fn from_name(name: &str) -> Self {
-        match name.to_lowercase().as_str() {
-            "nord" => Self::nord(),
-            "gruvbox" => Self::gruvbox(),
-            "dracula" => Self::dracula(),
-            "monokai" => Self::monokai(),
-            "cyberpunk" => Self::cyberpunk(),
-            "tokyo-night" | "tokyo_night" => Self::tokyo_night(),
-            "solarized-dark" | "solarized_dark" => Self::solarized_dark(),
-            "solarized-light" | "solarized_light" => Self::solarized_light(),
-            "one-dark" | "one_dark" => Self::one_dark(),
-            "material" => Self::material(),
-            "ayu-dark" | "ayu_dark" => Self::ayu_dark(),
-            "rosepine" | "rose-pine" | "rose_pine" => Self::rosepine(),
-            _ => Self::default(),
-        }
-    }
-
-    pub fn bg(&self) -> Color {
-        Color::Rgb(self.background[0], self.background[1], self.background[2])
-    }
-
-    pub fn fg(&self) -> Color {
-        Color::Rgb(self.foreground[0], self.foreground[1], self.foreground[2])
-    }
-
-    pub fn primary(&self) -> Color {
-        Color::Rgb(self.primary[0], self.primary[1], self.primary[2])
-    }
-
-    pub fn secondary(&self) -> Color {
-        Color::Rgb(self.secondary[0], self.secondary[1], self.secondary[2])
-    }
-
-    pub fn success(&self) -> Color {
-        Color::Rgb(self.success[0], self.success[1], self.success[2])
-    }
-
-    pub fn warning(&self) -> Color {
-        Color::Rgb(self.warning[0], self.warning[1], self.warning[2])
-    }
-
-    pub fn danger(&self) -> Color {
-        Color::Rgb(self.danger[0], self.danger[1], self.danger[2])
-    }
-
-    pub fn border(&self) -> Color {
-        Color::Rgb(self.border[0], self.border[1], self.border[2])
-    }
-
-    pub fn usage_color(&self, percent: f32) -> Color {
-        if percent < 50.0 {
-            self.success()
-        } else if percent < 80.0 {
-            self.warning()
-        } else {
-            self.danger()
-        }
-    }
-}
-
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub background: [u8; 3],
+    pub foreground: [u8; 3],
+    pub primary: [u8; 3],
+    pub secondary: [u8; 3],
+    pub success: [u8; 3],
+    pub warning: [u8; 3],
+    pub danger: [u8; 3],
+    pub border: [u8; 3],
+    /// Styles of single elements, replacing what they'd take from the palette.
+    pub styles: BTreeMap<Element, ElementStyle>,
+    pub thresholds: Thresholds,
+    /// Shade usage colors smoothly between the thresholds instead of in steps.
+    pub gradient: bool,
+    /// What the terminal can show; colors are mapped down to it.
+    pub depth: ColorDepth,
+}
+
+impl Theme {
+    pub fn default() -> Self {
+        Self {
+            background: [26, 27, 38],
+            foreground: [198, 208, 245],
+            primary: [137, 180, 250],
+            secondary: [203, 166, 247],
+            success: [166, 227, 161],
+            warning: [249, 226, 175],
+            danger: [243, 139, 168],
+            border: [88, 91, 112],
+            styles: BTreeMap::new(),
+            thresholds: Thresholds::default(),
+            gradient: false,
+            depth: ColorDepth::TrueColor,
+        }
+    }
+
+    pub fn nord() -> Self {
+        Self {
+            background: [46, 52, 64],
+            foreground: [236, 239, 244],
+            primary: [136, 192, 208],
+            secondary: [129, 161, 193],
+            success: [163, 190, 140],
+            warning: [235, 203, 139],
+            danger: [191, 97, 106],
+            border: [67, 76, 94],
+            styles: BTreeMap::new(),
+            thresholds: Thresholds::default(),
+            gradient: false,
+            depth: ColorDepth::TrueColor,
+        }
+    }
+
+    pub fn gruvbox() -> Self {
+        Self {
+            background: [40, 40, 40],
+            foreground: [235, 219, 178],
+            primary: [131, 165, 152],
+            secondary: [211, 134, 155],
+            success: [184, 187, 38],
+            warning: [250, 189, 47],
+            danger: [251, 73, 52],
+            border: [102, 92, 84],
+            styles: BTreeMap::new(),
+            thresholds: Thresholds::default(),
+            gradient: false,
+            depth: ColorDepth::TrueColor,
+        }
+    }
+
+    pub fn dracula() -> Self {
+        Self {
+            background: [40, 42, 54],
+            foreground: [248, 248, 242],
+            primary: [139, 233, 253],
+            secondary: [189, 147, 249],
+            success: [80, 250, 123],
+            warning: [241, 250, 140],
+            danger: [255, 85, 85],
+            border: [68, 71, 90],
+            styles: BTreeMap::new(),
+            thresholds: Thresholds::default(),
+            gradient: false,
+            depth: ColorDepth::TrueColor,
+        }
+    }
+
+    pub fn monokai() -> Self {
+        Self {
+            background: [39, 40, 34],
+            foreground: [248, 248, 240],
+            primary: [102, 217, 239],
+            secondary: [174, 129, 255],
+            success: [166, 226, 46],
+            warning: [244, 191, 117],
+            danger: [249, 38, 114],
+            border: [73, 72, 62],
+            styles: BTreeMap::new(),
+            thresholds: Thresholds::default(),
+            gradient: false,
+            depth: ColorDepth::TrueColor,
+        }
+    }
+
+    pub fn cyberpunk() -> Self {
+        Self {
+            background: [16, 18, 27],
+            foreground: [255, 0, 255],
+            primary: [0, 255, 255],
+            secondary: [255, 0, 128],
+            success: [0, 255, 0],
+            warning: [255, 255, 0],
+            danger: [255, 0, 100],
+            border: [138, 43, 226],
+            styles: BTreeMap::new(),
+            thresholds: Thresholds::default(),
+            gradient: false,
+            depth: ColorDepth::TrueColor,
+        }
+    }
+
+    pub fn tokyo_night() -> Self {
+        Self {
+            background: [26, 27, 38],
+            foreground: [192, 202, 245],
+            primary: [122, 162, 247],
+            secondary: [187, 154, 247],
+            success: [158, 206, 106],
+            warning: [224, 175, 104],
+            danger: [247, 118, 142],
+            border: [65, 72, 104],
+            styles: BTreeMap::new(),
+            thresholds: Thresholds::default(),
+            gradient: false,
+            depth: ColorDepth::TrueColor,
+        }
+    }
+
+    pub fn solarized_dark() -> Self {
+        Self {
+            background: [0, 43, 54],
+            foreground: [131, 148, 150],
+            primary: [38, 139, 210],
+            secondary: [108, 113, 196],
+            success: [133, 153, 0],
+            warning: [181, 137, 0],
+            danger: [220, 50, 47],
+            border: [7, 54, 66],
+            styles: BTreeMap::new(),
+            thresholds: Thresholds::default(),
+            gradient: false,
+            depth: ColorDepth::TrueColor,
+        }
+    }
+
+    pub fn solarized_light() -> Self {
+        Self {
+            background: [253, 246, 227],
+            foreground: [101, 123, 131],
+            primary: [38, 139, 210],
+            secondary: [108, 113, 196],
+            success: [133, 153, 0],
+            warning: [181, 137, 0],
+            danger: [220, 50, 47],
+            border: [238, 232, 213],
+            styles: BTreeMap::new(),
+            thresholds: Thresholds::default(),
+            gradient: false,
+            depth: ColorDepth::TrueColor,
+        }
+    }
+
+    pub fn one_dark() -> Self {
+        Self {
+            background: [40, 44, 52],
+            foreground: [171, 178, 191],
+            primary: [97, 175, 239],
+            secondary: [198, 120, 221],
+            success: [152, 195, 121],
+            warning: [229, 192, 123],
+            danger: [224, 108, 117],
+            border: [76, 82, 99],
+            styles: BTreeMap::new(),
+            thresholds: Thresholds::default(),
+            gradient: false,
+            depth: ColorDepth::TrueColor,
+        }
+    }
+
+    pub fn material() -> Self {
+        Self {
+            background: [38, 50, 56],
+            foreground: [236, 239, 241],
+            primary: [128, 203, 196],
+            secondary: [255, 202, 40],
+            success: [195, 232, 141],
+            warning: [255, 213, 79],
+            danger: [239, 83, 80],
+            border: [69, 90, 100],
+            styles: BTreeMap::new(),
+            thresholds: Thresholds::default(),
+            gradient: false,
+            depth: ColorDepth::TrueColor,
+        }
+    }
+
+    pub fn ayu_dark() -> Self {
+        Self {
+            background: [10, 14, 20],
+            foreground: [230, 237, 243],
+            primary: [89, 181, 249],
+            secondary: [223, 142, 255],
+            success: [186, 230, 126],
+            warning: [255, 180, 84],
+            danger: [242, 119, 122],
+            border: [15, 20, 25],
+            styles: BTreeMap::new(),
+            thresholds: Thresholds::default(),
+            gradient: false,
+            depth: ColorDepth::TrueColor,
+        }
+    }
+
+    pub fn rosepine() -> Self {
+        Self {
+            background: [25, 23, 36],
+            foreground: [224, 222, 244],
+            primary: [156, 207, 216],
+            secondary: [196, 167, 231],
+            success: [49, 116, 143],
+            warning: [246, 193, 119],
+            danger: [235, 111, 146],
+            border: [33, 32, 46],
+            styles: BTreeMap::new(),
+            thresholds: Thresholds::default(),
+            gradient: false,
+            depth: ColorDepth::TrueColor,
+        }
+    }
+
+    /// The built-in theme called `name`.
+    fn builtin(name: &str) -> Option<Self> {
+        let theme = match name {
+            "default" => Self::default(),
+            "nord" => Self::nord(),
+            "gruvbox" => Self::gruvbox(),
+            "dracula" => Self::dracula(),
+            "monokai" => Self::monokai(),
+            "cyberpunk" => Self::cyberpunk(),
+            "tokyo-night" => Self::tokyo_night(),
+            "solarized-dark" => Self::solarized_dark(),
+            "solarized-light" => Self::solarized_light(),
+            "one-dark" => Self::one_dark(),
+            "material" => Self::material(),
+            "ayu-dark" => Self::ayu_dark(),
+            "rosepine" => Self::rosepine(),
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    pub fn bg(&self) -> Color {
+        self.rgb(self.background)
+    }
+
+    pub fn fg(&self) -> Color {
+        self.rgb(self.foreground)
+    }
+
+    pub fn primary(&self) -> Color {
+        self.rgb(self.primary)
+    }
+
+    pub fn secondary(&self) -> Color {
+        self.rgb(self.secondary)
+    }
+
+    pub fn success(&self) -> Color {
+        self.rgb(self.success)
+    }
+
+    pub fn warning(&self) -> Color {
+        self.rgb(self.warning)
+    }
+
+    pub fn danger(&self) -> Color {
+        self.rgb(self.danger)
+    }
+
+    pub fn border(&self) -> Color {
+        self.rgb(self.border)
+    }
+
+    pub fn usage_color(&self, percent: f32) -> Color {
+        let (warning, danger) = (self.thresholds.warning(), self.thresholds.danger());
+        if self.gradient {
+            let rgb = if percent < warning {
+                mix(self.success, self.warning, percent / warning)
+            } else {
+                mix(
+                    self.warning,
+                    self.danger,
+                    (percent - warning) / (danger - warning),
+                )
+            };
+            return self.rgb(rgb);
+        }
+
+        if percent < warning {
+            self.success()
+        } else if percent < danger {
+            self.warning()
+        } else {
+            self.danger()
+        }
+    }
+
+    /// Panel titles.
+    pub fn title(&self) -> Style {
+        self.element(
+            Element::Title,
+            Style::default()
+                .fg(self.primary())
+                .add_modifier(Modifier::BOLD),
+        )
+    }
+
+    /// Labels in front of values, and table headers.
+    pub fn label(&self) -> Style {
+        self.element(
+            Element::Label,
+            Style::default()
+                .fg(self.secondary())
+                .add_modifier(Modifier::BOLD),
+        )
+    }
+
+    pub fn value(&self) -> Style {
+        self.element(Element::Value, Style::default().fg(self.fg()))
+    }
+
+    /// Text showing `percent` of something used. Without colors, the levels
+    /// past the thresholds are told apart by bold and reversed text.
+    pub fn usage(&self, percent: f32) -> Style {
+        if self.depth != ColorDepth::Mono {
+            return Style::default().fg(self.usage_color(percent));
+        }
+        match self.level(percent) {
+            0 => Style::default(),
+            1 => Style::default().add_modifier(Modifier::BOLD),
+            _ => Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED),
+        }
+    }
+
+    /// A gauge or bar showing `percent` of something used.
+    pub fn gauge(&self, percent: f32) -> Style {
+        self.element(
+            Element::Gauge,
+            Style::default().fg(self.usage_color(percent)),
+        )
+    }
+
+    /// What follows a gauge or bar past a threshold when there are no colors
+    /// to tell: ` !` past the warning threshold, ` !!` past the danger one.
+    pub fn usage_marker(&self, percent: f32) -> &'static str {
+        if self.depth != ColorDepth::Mono {
+            return "";
+        }
+        match self.level(percent) {
+            0 => "",
+            1 => " !",
+            _ => " !!",
+        }
+    }
+
+    /// 0 below the warning threshold, 1 below the danger threshold, 2 above.
+    fn level(&self, percent: f32) -> u8 {
+        if percent < self.thresholds.warning() {
+            0
+        } else if percent < self.thresholds.danger() {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Text on a `color` background, such as a banner or badge.
+    pub fn badge(&self, color: Color) -> Style {
+        if self.depth == ColorDepth::Mono {
+            return Style::default().add_modifier(Modifier::REVERSED);
+        }
+        Style::default().fg(self.bg()).bg(color)
+    }
+
+    /// The border of the focused panel.
+    pub fn focused_border(&self) -> Style {
+        if self.depth == ColorDepth::Mono {
+            return Style::default().add_modifier(Modifier::BOLD);
+        }
+        Style::default().fg(self.primary())
+    }
+
+    /// Cells shaded to mark a selected range.
+    pub fn shade(&self) -> Style {
+        if self.depth == ColorDepth::Mono {
+            return Style::default().add_modifier(Modifier::REVERSED);
+        }
+        Style::default().bg(self.border())
+    }
+
+    /// A history chart's line, `color` unless the theme sets one.
+    pub fn chart(&self, color: Color) -> Style {
+        self.element(Element::Chart, Style::default().fg(color))
+    }
+
+    /// The active screen's tab.
+    pub fn selection(&self) -> Style {
+        self.element(
+            Element::Selection,
+            self.badge(self.primary()).add_modifier(Modifier::BOLD),
+        )
+    }
+
+    pub fn alert_banner(&self) -> Style {
+        self.element(Element::AlertBanner, self.badge(self.danger()))
+    }
+
+    /// `base` with whatever the theme sets for `element` in its place.
+    fn element(&self, element: Element, base: Style) -> Style {
+        let Some(style) = self.styles.get(&element) else {
+            return base;
+        };
+        let mut base = base;
+        if let Some(fg) = style.fg {
+            base = base.fg(self.color(fg));
+        }
+        if let Some(bg) = style.bg {
+            base = base.bg(self.color(bg));
+        }
+        if let Some(modifiers) = &style.modifiers {
+            let modifiers = modifiers
+                .iter()
+                .fold(Modifier::empty(), |all, m| all | m.modifier());
+            base = base
+                .remove_modifier(Modifier::all())
+                .add_modifier(modifiers);
+        }
+        base
+    }
+
+    fn color(&self, color: ThemeColor) -> Color {
+        let rgb = match color {
+            ThemeColor::Rgb(rgb) => rgb,
+            ThemeColor::Slot(Slot::Background) => self.background,
+            ThemeColor::Slot(Slot::Foreground) => self.foreground,
+            ThemeColor::Slot(Slot::Primary) => self.primary,
+            ThemeColor::Slot(Slot::Secondary) => self.secondary,
+            ThemeColor::Slot(Slot::Success) => self.success,
+            ThemeColor::Slot(Slot::Warning) => self.warning,
+            ThemeColor::Slot(Slot::Danger) => self.danger,
+            ThemeColor::Slot(Slot::Border) => self.border,
+        };
+        self.rgb(rgb)
+    }
+
+    fn rgb(&self, rgb: [u8; 3]) -> Color {
+        self.depth.color(rgb)
+    }
+}
+
+/// How many colors the terminal can show.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorDepth {
+    #[default]
+    #[value(name = "truecolor")]
+    TrueColor,
+    #[value(name = "256")]
+    Ansi256,
+    #[value(name = "16")]
+    Ansi16,
+    /// No colors at all, for `NO_COLOR` and dumb terminals.
+    #[value(name = "mono")]
+    Mono,
+}
+
+/// The 16 ANSI colors as xterm draws them by default.
+const ANSI_16: [(Color, [u8; 3]); 16] = [
+    (Color::Black, [0, 0, 0]),
+    (Color::Red, [205, 0, 0]),
+    (Color::Green, [0, 205, 0]),
+    (Color::Yellow, [205, 205, 0]),
+    (Color::Blue, [0, 0, 238]),
+    (Color::Magenta, [205, 0, 205]),
+    (Color::Cyan, [0, 205, 205]),
+    (Color::Gray, [229, 229, 229]),
+    (Color::DarkGray, [127, 127, 127]),
+    (Color::LightRed, [255, 0, 0]),
+    (Color::LightGreen, [0, 255, 0]),
+    (Color::LightYellow, [255, 255, 0]),
+    (Color::LightBlue, [92, 92, 255]),
+    (Color::LightMagenta, [255, 0, 255]),
+    (Color::LightCyan, [0, 255, 255]),
+    (Color::White, [255, 255, 255]),
+];
+
+/// Channel levels of the 6x6x6 color cube in the 256-color palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorDepth {
+    /// Works out the color depth from the environment: `NO_COLOR`, then
+    /// `COLORTERM`, then `TERM`.
+    pub fn detect() -> Self {
+        let var = |name| env::var(name).unwrap_or_default().to_lowercase();
+        if !var("NO_COLOR").is_empty() {
+            return ColorDepth::Mono;
+        }
+        if matches!(var("COLORTERM").as_str(), "truecolor" | "24bit") {
+            return ColorDepth::TrueColor;
+        }
+
+        let term = var("TERM");
+        if term.is_empty() {
+            // Windows terminals don't set TERM and all take RGB colors now.
+            return if cfg!(windows) {
+                ColorDepth::TrueColor
+            } else {
+                ColorDepth::Ansi16
+            };
+        }
+        if term == "dumb" {
+            ColorDepth::Mono
+        } else if term.contains("truecolor") || term.contains("direct") {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            // The Linux console, serial consoles, plain xterm and screen.
+            ColorDepth::Ansi16
+        }
+    }
+
+    /// The closest color to `rgb` the terminal can show.
+    pub fn color(self, rgb: [u8; 3]) -> Color {
+        match self {
+            ColorDepth::TrueColor => Color::Rgb(rgb[0], rgb[1], rgb[2]),
+            ColorDepth::Ansi256 => Color::Indexed(nearest_256(rgb)),
+            ColorDepth::Ansi16 => ANSI_16
+                .iter()
+                .min_by_key(|(_, candidate)| distance(rgb, *candidate))
+                .map(|(color, _)| *color)
+                .unwrap_or(Color::Reset),
+            ColorDepth::Mono => Color::Reset,
+        }
+    }
+}
+
+/// The closest entry of the 256-color palette past the 16 ANSI colors, which
+/// terminals are free to redefine: either the color cube or the gray ramp.
+fn nearest_256(rgb: [u8; 3]) -> u8 {
+    let level = |channel: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - channel as i32).abs())
+            .unwrap_or(0)
+    };
+    let (r, g, b) = (level(rgb[0]), level(rgb[1]), level(rgb[2]));
+    let cube = [CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]];
+    let cube_index = 16 + 36 * r + 6 * g + b;
+
+    let average = (rgb[0] as u32 + rgb[1] as u32 + rgb[2] as u32) / 3;
+    let step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 8 + 10 * step;
+
+    if distance(rgb, [gray, gray, gray]) < distance(rgb, cube) {
+        232 + step
+    } else {
+        cube_index as u8
+    }
+}
+
+fn distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    (0..3)
+        .map(|i| (a[i] as i32 - b[i] as i32).pow(2) as u32)
+        .sum()
+}
+
+/// The color `t` of the way from `from` to `to`.
+fn mix(from: [u8; 3], to: [u8; 3], t: f32) -> [u8; 3] {
+    let t = if t.is_finite() {
+        t.clamp(0.0, 1.0)
+    } else {
+        1.0
+    };
+    let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    [
+        channel(from[0], to[0]),
+        channel(from[1], to[1]),
+        channel(from[2], to[2]),
+    ]
+}
+
+/// Parts of the interface a theme can style on their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Element {
+    Title,
+    Label,
+    Value,
+    Gauge,
+    Chart,
+    Selection,
+    AlertBanner,
+}
+
+/// An element's style; anything left out keeps the element's usual look.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ElementStyle {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fg: Option<ThemeColor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bg: Option<ThemeColor>,
+    /// Replaces the element's modifiers; `[]` clears them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modifiers: Option<Vec<ThemeModifier>>,
+}
+
+impl ElementStyle {
+    fn merge(&mut self, other: &ElementStyle) {
+        self.fg = other.fg.or(self.fg);
+        self.bg = other.bg.or(self.bg);
+        if other.modifiers.is_some() {
+            self.modifiers = other.modifiers.clone();
+        }
+    }
+}
+
+/// A color as `[red, green, blue]`, or the name of one of the theme's colors.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ThemeColor {
+    Rgb([u8; 3]),
+    Slot(Slot),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Slot {
+    Background,
+    Foreground,
+    Primary,
+    Secondary,
+    Success,
+    Warning,
+    Danger,
+    Border,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeModifier {
+    Bold,
+    Dim,
+    Italic,
+    Underlined,
+    Reversed,
+    CrossedOut,
+}
+
+impl ThemeModifier {
+    fn modifier(self) -> Modifier {
+        match self {
+            ThemeModifier::Bold => Modifier::BOLD,
+            ThemeModifier::Dim => Modifier::DIM,
+            ThemeModifier::Italic => Modifier::ITALIC,
+            ThemeModifier::Underlined => Modifier::UNDERLINED,
+            ThemeModifier::Reversed => Modifier::REVERSED,
+            ThemeModifier::CrossedOut => Modifier::CROSSED_OUT,
+        }
+    }
+}
+
+/// Usage percentages at which colors turn from success to warning and from
+/// warning to danger.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Thresholds {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub danger: Option<f32>,
+}
+
+impl Thresholds {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn warning(&self) -> f32 {
+        self.warning.unwrap_or(50.0)
+    }
+
+    pub fn danger(&self) -> f32 {
+        self.danger.unwrap_or(80.0)
+    }
+
+    /// Why the thresholds can't be used as they are, if they can't.
+    pub fn problem(&self) -> Option<String> {
+        let (warning, danger) = (self.warning(), self.danger());
+        if !(0.0..=100.0).contains(&warning) || !(0.0..=100.0).contains(&danger) {
+            Some("thresholds must be between 0 and 100".to_string())
+        } else if warning >= danger {
+            Some(format!(
+                "the warning threshold ({}) must be below the danger threshold ({})",
+                warning, danger
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// Names of the built-in themes, in cycling order.
+const BUILTIN_THEMES: [&str; 13] = [
+    "default",
+    "nord",
+    "gruvbox",
+    "dracula",
+    "monokai",
+    "cyberpunk",
+    "tokyo-night",
+    "solarized-dark",
+    "solarized-light",
+    "one-dark",
+    "material",
+    "ayu-dark",
+    "rosepine",
+];
+
+/// A theme as written in a theme file or the config's `[theme]` table: the
+/// colors it sets on top of the theme it `extends`, or the default theme.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeFile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<[u8; 3]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub foreground: Option<[u8; 3]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary: Option<[u8; 3]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secondary: Option<[u8; 3]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success: Option<[u8; 3]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<[u8; 3]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub danger: Option<[u8; 3]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border: Option<[u8; 3]>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub styles: BTreeMap<Element, ElementStyle>,
+    #[serde(skip_serializing_if = "Thresholds::is_empty")]
+    pub thresholds: Thresholds,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gradient: Option<bool>,
+}
+
+impl ThemeFile {
+    fn apply(&self, mut theme: Theme) -> Theme {
+        let colors = [
+            (&mut theme.background, self.background),
+            (&mut theme.foreground, self.foreground),
+            (&mut theme.primary, self.primary),
+            (&mut theme.secondary, self.secondary),
+            (&mut theme.success, self.success),
+            (&mut theme.warning, self.warning),
+            (&mut theme.danger, self.danger),
+            (&mut theme.border, self.border),
+        ];
+        for (color, set) in colors {
+            if let Some(set) = set {
+                *color = set;
+            }
+        }
+        for (element, style) in &self.styles {
+            theme.styles.entry(*element).or_default().merge(style);
+        }
+        theme.thresholds.warning = self.thresholds.warning.or(theme.thresholds.warning);
+        theme.thresholds.danger = self.thresholds.danger.or(theme.thresholds.danger);
+        theme.gradient = self.gradient.unwrap_or(theme.gradient);
+        theme
+    }
+}
+
+/// The `theme` config key: the name of a registered theme, or a table of
+/// colors of its own.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ThemeChoice {
+    Name(String),
+    Custom(ThemeFile),
+}
+
+// Written out rather than derived with `untagged`, which would replace any
+// mistake inside a `[theme]` table with a generic "did not match" error.
+impl<'de> Deserialize<'de> for ThemeChoice {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = ThemeChoice;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a theme name or a table of colors")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, name: &str) -> Result<ThemeChoice, E> {
+                Ok(ThemeChoice::Name(name.to_string()))
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                map: A,
+            ) -> Result<ThemeChoice, A::Error> {
+                ThemeFile::deserialize(serde::de::value::MapAccessDeserializer::new(map))
+                    .map(ThemeChoice::Custom)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+impl Default for ThemeChoice {
+    fn default() -> Self {
+        ThemeChoice::Name("default".to_string())
+    }
+}
+
+impl ThemeChoice {
+    /// What the footer calls the theme.
+    pub fn label(&self) -> &str {
+        match self {
+            ThemeChoice::Name(name) => name,
+            ThemeChoice::Custom(_) => "custom",
+        }
+    }
+}
+
+/// The built-in themes followed by the user's theme files, in the order the
+/// theme key cycles through them.
+#[derive(Debug, Clone)]
+pub struct ThemeRegistry {
+    themes: Vec<(String, Theme)>,
+    depth: ColorDepth,
+}
+
+impl Default for ThemeRegistry {
+    fn default() -> Self {
+        Self {
+            themes: BUILTIN_THEMES
+                .iter()
+                .filter_map(|name| Some((name.to_string(), Theme::builtin(name)?)))
+                .collect(),
+            depth: ColorDepth::default(),
+        }
+    }
+}
+
+impl ThemeRegistry {
+    /// The built-in themes plus every `*.toml` file in `dir`, named after the
+    /// file. A file named like a built-in theme replaces it. Returns a message
+    /// for each file that couldn't be used. Themes handed out are mapped down
+    /// to `depth`.
+    pub fn load(dir: Option<&Path>, depth: ColorDepth) -> (Self, Vec<String>) {
+        let mut registry = Self {
+            depth,
+            ..Self::default()
+        };
+        let mut problems = Vec::new();
+
+        let mut files = Vec::new();
+        if let Some(entries) = dir.and_then(|dir| fs::read_dir(dir).ok()) {
+            for path in entries.filter_map(|entry| Some(entry.ok()?.path())) {
+                if path.extension().is_none_or(|ext| ext != "toml") {
+                    continue;
+                }
+                let Some(name) = path
+                    .file_stem()
+                    .map(|stem| canonical(&stem.to_string_lossy()))
+                else {
+                    continue;
+                };
+                let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                let mut unknown = Vec::new();
+                let parsed = fs::read_to_string(&path)
+                    .map_err(|err| err.to_string())
+                    .and_then(|content| {
+                        serde_ignored::deserialize(toml::Deserializer::new(&content), |key| {
+                            unknown.push(key.to_string())
+                        })
+                        .map_err(|err: toml::de::Error| err.message().trim().replace('\n', "; "))
+                    });
+                for key in unknown {
+                    problems.push(format!(
+                        "themes/{}: unknown key `{}` is ignored",
+                        file_name, key
+                    ));
+                }
+                match parsed {
+                    Ok(file) => files.push((name, file)),
+                    Err(err) => problems.push(format!("themes/{}: {}", file_name, err)),
+                }
+            }
+        }
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (name, file) in &files {
+            let resolved =
+                resolve_file(name, file, &files, &mut vec![name.clone()]).and_then(|theme| {
+                    match theme.thresholds.problem() {
+                        Some(problem) => Err(problem),
+                        None => Ok(theme),
+                    }
+                });
+            match resolved {
+                Ok(theme) => registry.insert(name.clone(), theme),
+                Err(err) => problems.push(format!("theme `{}`: {}", name, err)),
+            }
+        }
+
+        (registry, problems)
+    }
+
+    fn insert(&mut self, name: String, theme: Theme) {
+        match self
+            .themes
+            .iter_mut()
+            .find(|(existing, _)| *existing == name)
+        {
+            Some(slot) => slot.1 = theme,
+            None => self.themes.push((name, theme)),
+        }
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.themes.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<Theme> {
+        let name = canonical(name);
+        let (_, theme) = self.themes.iter().find(|(n, _)| *n == name)?;
+        Some(Theme {
+            depth: self.depth,
+            ..theme.clone()
+        })
+    }
+
+    /// The theme after `name` in cycling order, wrapping around.
+    pub fn next_after(&self, name: &str) -> &str {
+        let name = canonical(name);
+        let next = match self.themes.iter().position(|(n, _)| *n == name) {
+            Some(index) => (index + 1) % self.themes.len(),
+            None => 0,
+        };
+        &self.themes[next].0
+    }
+
+    pub fn resolve(&self, choice: &ThemeChoice) -> Result<Theme, String> {
+        match choice {
+            ThemeChoice::Name(name) => self.get(name).ok_or_else(|| self.unknown(name)),
+            ThemeChoice::Custom(file) => {
+                let base = match &file.extends {
+                    Some(name) => self.get(name).ok_or_else(|| self.unknown(name))?,
+                    None => Theme {
+                        depth: self.depth,
+                        ..Theme::default()
+                    },
+                };
+                Ok(file.apply(base))
+            }
+        }
+    }
+
+    fn unknown(&self, name: &str) -> String {
+        format!(
+            "unknown theme `{}`; available themes: {}",
+            name,
+            self.names().join(", ")
+        )
+    }
+}
+
+/// Resolves a theme file against the other files and the built-ins.
+/// `visiting` holds the chain of names being resolved, to catch cycles.
+fn resolve_file(
+    name: &str,
+    file: &ThemeFile,
+    files: &[(String, ThemeFile)],
+    visiting: &mut Vec<String>,
+) -> Result<Theme, String> {
+    let Some(parent) = file.extends.as_deref().map(canonical) else {
+        return Ok(file.apply(Theme::default()));
+    };
+
+    // A file extending its own name builds on the built-in theme it replaces.
+    let parent_file = files.iter().find(|(n, _)| *n == parent && parent != name);
+    let base = match parent_file {
+        Some((parent, parent_file)) => {
+            if visiting.contains(parent) {
+                return Err(format!("`extends` loops back to `{}`", parent));
+            }
+            visiting.push(parent.clone());
+            resolve_file(parent, parent_file, files, visiting)?
+        }
+        None => {
+            Theme::builtin(&parent).ok_or_else(|| format!("extends unknown theme `{}`", parent))?
+        }
+    };
+    Ok(file.apply(base))
+}
+
+/// Theme names are matched case-insensitively, with `_` and `-` alike.
+fn canonical(name: &str) -> String {
+    match name.to_lowercase().replace('_', "-").as_str() {
+        "rose-pine" => "rosepine".to_string(),
+        name => name.to_string(),
+    }
+}
@@ -1,541 +1,1902 @@
-use crate::app::App;
-use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Modifier, Style},
-    text::{Line, Span},
-    widgets::{Block, Borders, Gauge, List, ListItem, Paragraph, Sparkline},
-    Frame,
-};
-
-pub fn render(f: &mut Frame, app: &App) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage(35),
-            Constraint::Percentage(60),
-            Constraint::Min(3),
-        ])
-        .split(f.area());
-
-    render_system_info(f, chunks[0], app);
-    render_monitoring(f, chunks[1], app);
-    render_footer(f, chunks[2], app);
-}
-
-fn render_system_info(f: &mut Frame, area: Rect, app: &App) {
-    let theme = &app.config.theme;
-    let info = &app.system_info;
-
-    let border_type = if app.config.rounded_borders {
-        ratatui::widgets::BorderType::Rounded
-    } else {
-        ratatui::widgets::BorderType::Plain
-    };
-
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .border_type(border_type)
-        .border_style(Style::default().fg(theme.border()))
-        .title(Span::styled(
-            " System Information ",
-            Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD),
-        ))
-        .style(Style::default().bg(theme.bg()));
-
-    let inner = block.inner(area);
-    f.render_widget(block, area);
-
-    let main_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Length(12), Constraint::Min(30)])
-        .split(inner);
-
-    let dog_art = vec![
-        Line::from(""),
-        Line::from(Span::styled("   ʕ•ᴥ•ʔ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD))),
-        Line::from(Span::styled("  ∪￣￣∪", Style::default().fg(theme.secondary()))),
-        Line::from(""),
-    ];
-
-    let dog_paragraph = Paragraph::new(dog_art)
-        .style(Style::default().bg(theme.bg()))
-        .alignment(ratatui::layout::Alignment::Center);
-
-    f.render_widget(dog_paragraph, main_chunks[0]);
-
-    let info_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(main_chunks[1]);
-
-    let left_info = vec![
-        Line::from(vec![
-            Span::styled("User: ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-            Span::styled(format!("{}@{}", info.username, info.hostname), Style::default().fg(theme.fg())),
-        ]),
-        Line::from(vec![
-            Span::styled("OS: ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-            Span::styled(&info.os_name, Style::default().fg(theme.fg())),
-        ]),
-        Line::from(vec![
-            Span::styled("Distro: ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-            Span::styled(&info.distribution, Style::default().fg(theme.fg())),
-        ]),
-        Line::from(vec![
-            Span::styled("Kernel: ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-            Span::styled(&info.kernel_version, Style::default().fg(theme.fg())),
-        ]),
-        Line::from(vec![
-            Span::styled("Arch: ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-            Span::styled(&info.architecture, Style::default().fg(theme.fg())),
-        ]),
-        Line::from(vec![
-            Span::styled("Shell: ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-            Span::styled(&info.shell, Style::default().fg(theme.fg())),
-        ]),
-        Line::from(vec![
-            Span::styled("Processes: ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-            Span::styled(format!("{}", info.processes_count), Style::default().fg(theme.fg())),
-        ]),
-        Line::from(vec![
-            Span::styled("Boot Time: ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-            Span::styled(&info.boot_time, Style::default().fg(theme.fg())),
-        ]),
-    ];
-
-    let mut right_info = vec![
-        Line::from(vec![
-            Span::styled("CPU: ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-            Span::styled(&info.cpu_name, Style::default().fg(theme.fg())),
-        ]),
-        Line::from(vec![
-            Span::styled("Cores: ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-            Span::styled(format!("{} ({} physical)", info.cpu_cores, info.cpu_physical_cores), Style::default().fg(theme.fg())),
-        ]),
-        Line::from(vec![
-            Span::styled("Frequency: ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-            Span::styled(&info.cpu_frequency, Style::default().fg(theme.fg())),
-        ]),
-        Line::from(vec![
-            Span::styled("Memory: ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-            Span::styled(format!("{:.1} GB", info.memory_gb()), Style::default().fg(theme.fg())),
-        ]),
-        Line::from(vec![
-            Span::styled("Uptime: ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-            Span::styled(info.uptime_string(), Style::default().fg(theme.fg())),
-        ]),
-    ];
-
-    if info.display_info.resolution != "Unknown" && info.display_info.resolution != "N/A" {
-        right_info.push(Line::from(vec![
-            Span::styled("Display: ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-            Span::styled(format!("{} @ {}", info.display_info.resolution, info.display_info.refresh_rate), Style::default().fg(theme.fg())),
-        ]));
-    }
-
-    if info.audio_info.default_device != "Unknown" && info.audio_info.default_device != "N/A" {
-        let audio_device = if info.audio_info.default_device.len() > 35 {
-            format!("{}...", &info.audio_info.default_device[..32])
-        } else {
-            info.audio_info.default_device.clone()
-        };
-        right_info.push(Line::from(vec![
-            Span::styled("Audio: ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-            Span::styled(audio_device, Style::default().fg(theme.fg())),
-        ]));
-    }
-
-    let left_paragraph = Paragraph::new(left_info).style(Style::default().bg(theme.bg()));
-    let right_paragraph = Paragraph::new(right_info).style(Style::default().bg(theme.bg()));
-
-    f.render_widget(left_paragraph, info_chunks[0]);
-    f.render_widget(right_paragraph, info_chunks[1]);
-}
-
-fn render_monitoring(f: &mut Frame, area: Rect, app: &App) {
-    let chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(area);
-
-    render_cpu_memory(f, chunks[0], app);
-    render_disk_network(f, chunks[1], app);
-}
-
-fn render_cpu_memory(f: &mut Frame, area: Rect, app: &App) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(area);
-
-    render_cpu(f, chunks[0], app);
-    render_memory(f, chunks[1], app);
-}
-
-fn render_cpu(f: &mut Frame, area: Rect, app: &App) {
-    let theme = &app.config.theme;
-    let cpu_stats = app.monitor.cpu_stats();
-
-    let border_type = if app.config.rounded_borders {
-        ratatui::widgets::BorderType::Rounded
-    } else {
-        ratatui::widgets::BorderType::Plain
-    };
-
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .border_type(border_type)
-        .border_style(Style::default().fg(theme.border()))
-        .title(Span::styled(
-            format!(" CPU Usage: {:.1}% ", cpu_stats.global_usage),
-            Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD),
-        ))
-        .style(Style::default().bg(theme.bg()));
-
-    let inner = block.inner(area);
-    f.render_widget(block, area);
-
-    let cpu_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Min(3), Constraint::Min(5)])
-        .margin(1)
-        .split(inner);
-
-    let gauge_color = theme.usage_color(cpu_stats.global_usage);
-    let gauge = Gauge::default()
-        .gauge_style(Style::default().fg(gauge_color))
-        .ratio(cpu_stats.global_usage as f64 / 100.0)
-        .label(format!("{:.1}%", cpu_stats.global_usage));
-
-    f.render_widget(gauge, cpu_chunks[0]);
-
-    let history = app.monitor.cpu_history();
-    if !history.is_empty() {
-        let history_u64: Vec<u64> = history.iter().map(|&v| v as u64).collect();
-        let sparkline = Sparkline::default()
-            .data(&history_u64)
-            .style(Style::default().fg(gauge_color))
-            .max(100);
-
-        f.render_widget(sparkline, cpu_chunks[1]);
-    }
-}
-
-fn render_memory(f: &mut Frame, area: Rect, app: &App) {
-    let theme = &app.config.theme;
-    let mem_stats = app.monitor.memory_stats();
-
-    let border_type = if app.config.rounded_borders {
-        ratatui::widgets::BorderType::Rounded
-    } else {
-        ratatui::widgets::BorderType::Plain
-    };
-
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .border_type(border_type)
-        .border_style(Style::default().fg(theme.border()))
-        .title(Span::styled(
-            format!(" Memory: {:.1}% ", mem_stats.used_percent()),
-            Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD),
-        ))
-        .style(Style::default().bg(theme.bg()));
-
-    let inner = block.inner(area);
-    f.render_widget(block, area);
-
-    let ram_color = theme.usage_color(mem_stats.used_percent());
-    let ram_available = mem_stats.total_gb() - mem_stats.used_gb();
-    
-    if mem_stats.swap_total > 0 {
-        let mem_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(2),
-                Constraint::Length(1),
-                Constraint::Length(2),
-            ])
-            .horizontal_margin(1)
-            .vertical_margin(1)
-            .split(inner);
-
-        let ram_info = vec![
-            Line::from(vec![
-                Span::styled("RAM: ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-                Span::styled(
-                    format!("{:.1} GB used • {:.1} GB free • {:.1}%", 
-                        mem_stats.used_gb(), 
-                        ram_available,
-                        mem_stats.used_percent()
-                    ),
-                    Style::default().fg(theme.fg()),
-                ),
-            ]),
-        ];
-        
-        let ram_paragraph = Paragraph::new(ram_info).style(Style::default().bg(theme.bg()));
-        f.render_widget(ram_paragraph, mem_chunks[0]);
-
-        let ram_gauge = Gauge::default()
-            .gauge_style(Style::default().fg(ram_color).add_modifier(Modifier::BOLD))
-            .ratio((mem_stats.used_percent() as f64 / 100.0).clamp(0.0, 1.0))
-            .label("");
-
-        f.render_widget(ram_gauge, Rect::new(
-            mem_chunks[0].x,
-            mem_chunks[0].y + 1,
-            mem_chunks[0].width,
-            1,
-        ));
-
-        let swap_color = theme.usage_color(mem_stats.swap_percent());
-        let swap_available = mem_stats.swap_total_gb() - mem_stats.swap_used_gb();
-        
-        let swap_info = vec![
-            Line::from(vec![
-                Span::styled("Swap: ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-                Span::styled(
-                    format!("{:.1} GB used • {:.1} GB free • {:.1}%", 
-                        mem_stats.swap_used_gb(), 
-                        swap_available,
-                        mem_stats.swap_percent()
-                    ),
-                    Style::default().fg(theme.fg()),
-                ),
-            ]),
-        ];
-        
-        let swap_paragraph = Paragraph::new(swap_info).style(Style::default().bg(theme.bg()));
-        f.render_widget(swap_paragraph, mem_chunks[2]);
-
-        let swap_gauge = Gauge::default()
-            .gauge_style(Style::default().fg(swap_color).add_modifier(Modifier::BOLD))
-            .ratio((mem_stats.swap_percent() as f64 / 100.0).clamp(0.0, 1.0))
-            .label("");
-
-        f.render_widget(swap_gauge, Rect::new(
-            mem_chunks[2].x,
-            mem_chunks[2].y + 1,
-            mem_chunks[2].width,
-            1,
-        ));
-    } else {
-        let mem_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Length(2)])
-            .horizontal_margin(1)
-            .vertical_margin(1)
-            .split(inner);
-
-        let ram_info = vec![
-            Line::from(vec![
-                Span::styled("RAM: ", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-                Span::styled(
-                    format!("{:.1} GB used • {:.1} GB free • {:.1}%", 
-                        mem_stats.used_gb(), 
-                        ram_available,
-                        mem_stats.used_percent()
-                    ),
-                    Style::default().fg(theme.fg()),
-                ),
-            ]),
-        ];
-        
-        let ram_paragraph = Paragraph::new(ram_info).style(Style::default().bg(theme.bg()));
-        f.render_widget(ram_paragraph, mem_chunks[0]);
-
-        let ram_gauge = Gauge::default()
-            .gauge_style(Style::default().fg(ram_color).add_modifier(Modifier::BOLD))
-            .ratio((mem_stats.used_percent() as f64 / 100.0).clamp(0.0, 1.0))
-            .label("");
-
-        f.render_widget(ram_gauge, Rect::new(
-            mem_chunks[0].x,
-            mem_chunks[0].y + 1,
-            mem_chunks[0].width,
-            1,
-        ));
-    }
-}
-
-fn render_disk_network(f: &mut Frame, area: Rect, app: &App) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(area);
-
-    render_disks(f, chunks[0], app);
-    render_network(f, chunks[1], app);
-}
-
-fn render_disks(f: &mut Frame, area: Rect, app: &App) {
-    let theme = &app.config.theme;
-    let disk_stats = app.monitor.disk_stats();
-
-    let border_type = if app.config.rounded_borders {
-        ratatui::widgets::BorderType::Rounded
-    } else {
-        ratatui::widgets::BorderType::Plain
-    };
-
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .border_type(border_type)
-        .border_style(Style::default().fg(theme.border()))
-        .title(Span::styled(
-            " Disk Usage ",
-            Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD),
-        ))
-        .style(Style::default().bg(theme.bg()));
-
-    let inner = block.inner(area);
-    f.render_widget(block, area);
-
-    let disk_items: Vec<ListItem> = disk_stats
-        .iter()
-        .enumerate()
-        .map(|(idx, disk)| {
-            let disk_label = if disk.name.is_empty() {
-                format!("HD{}", idx + 1)
-            } else {
-                let name = disk.name.replace("\\", "").replace(".", "");
-                if name.len() > 4 {
-                    format!("{}", &name[..4])
-                } else {
-                    name
-                }
-            };
-
-            let bar_width: usize = 15;
-            let filled = ((disk.used_percent() / 100.0) * bar_width as f32) as usize;
-            let empty = bar_width.saturating_sub(filled);
-            let bar = format!("{}{}", "█".repeat(filled), "░".repeat(empty));
-
-            let color = theme.usage_color(disk.used_percent());
-
-            ListItem::new(Line::from(vec![
-                Span::styled(format!("{:<4} ", disk_label), Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
-                Span::styled(format!("[{}] ", disk.disk_type), Style::default().fg(theme.primary())),
-                Span::styled(bar, Style::default().fg(color)),
-                Span::styled(
-                    format!(" {:.0}/{:.0}GB", disk.used_gb(), disk.total_gb()),
-                    Style::default().fg(theme.fg()),
-                ),
-            ]))
-        })
-        .collect();
-
-    let list = List::new(disk_items).style(Style::default().bg(theme.bg()));
-
-    f.render_widget(list, inner);
-}
-
-fn render_network(f: &mut Frame, area: Rect, app: &App) {
-    let theme = &app.config.theme;
-    let network = &app.network;
-
-    let border_type = if app.config.rounded_borders {
-        ratatui::widgets::BorderType::Rounded
-    } else {
-        ratatui::widgets::BorderType::Plain
-    };
-
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .border_type(border_type)
-        .border_style(Style::default().fg(theme.border()))
-        .title(Span::styled(
-            " Network ",
-            Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD),
-        ))
-        .style(Style::default().bg(theme.bg()));
-
-    let inner = block.inner(area);
-    f.render_widget(block, area);
-
-    let network_info = vec![
-        Line::from(vec![
-            Span::styled("↓ Download: ", Style::default().fg(theme.success()).add_modifier(Modifier::BOLD)),
-            Span::styled(
-                crate::system::NetworkStats::format_rate(network.rx_rate_mbps() * 1_024.0 * 1_024.0),
-                Style::default().fg(theme.fg()),
-            ),
-        ]),
-        Line::from(vec![
-            Span::styled("↑ Upload: ", Style::default().fg(theme.danger()).add_modifier(Modifier::BOLD)),
-            Span::styled(
-                crate::system::NetworkStats::format_rate(network.tx_rate_mbps() * 1_024.0 * 1_024.0),
-                Style::default().fg(theme.fg()),
-            ),
-        ]),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("Total RX: ", Style::default().fg(theme.secondary())),
-            Span::styled(format!("{:.2} GB", network.total_received_gb()), Style::default().fg(theme.fg())),
-        ]),
-        Line::from(vec![
-            Span::styled("Total TX: ", Style::default().fg(theme.secondary())),
-            Span::styled(format!("{:.2} GB", network.total_transmitted_gb()), Style::default().fg(theme.fg())),
-        ]),
-    ];
-
-    let paragraph = Paragraph::new(network_info)
-        .style(Style::default().bg(theme.bg()))
-        .block(Block::default());
-
-    f.render_widget(paragraph, inner);
-}
-
-fn render_footer(f: &mut Frame, area: Rect, app: &App) {
-    let theme = &app.config.theme;
-
-    let footer_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Min(30), Constraint::Length(20)])
-        .split(area);
-
-    let footer_text = Line::from(vec![
-        Span::styled(" [Q]", Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD)),
-        Span::styled(" Quit  ", Style::default().fg(theme.fg())),
-        Span::styled("[R]", Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD)),
-        Span::styled(" Refresh  ", Style::default().fg(theme.fg())),
-        Span::styled("[T]", Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD)),
-        Span::styled(" Theme ", Style::default().fg(theme.fg())),
-    ]);
-
-    let footer = Paragraph::new(footer_text)
-        .style(Style::default().bg(theme.bg()).fg(theme.fg()))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_type(if app.config.rounded_borders {
-                    ratatui::widgets::BorderType::Rounded
-                } else {
-                    ratatui::widgets::BorderType::Plain
-                })
-                .border_style(Style::default().fg(theme.border())),
-        );
-
-    let credit_text = Line::from(vec![
-        Span::styled(" made by ", Style::default().fg(theme.fg())),
-        Span::styled("gui기กีギ", Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD)),
-    ]);
-
-    let credit = Paragraph::new(credit_text)
-        .style(Style::default().bg(theme.bg()).fg(theme.fg()))
-        .alignment(ratatui::layout::Alignment::Center)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_type(if app.config.rounded_borders {
-                    ratatui::widgets::BorderType::Rounded
-                } else {
-                    ratatui::widgets::BorderType::Plain
-                })
-                .border_style(Style::default().fg(theme.border())),
-        );
-
-    f.render_widget(footer, footer_chunks[0]);
-    f.render_widget(credit, footer_chunks[1]);
-}
-
+use crate::alerts::AlertState;
+use crate::app::{format_millis, format_span, App};
+use crate::keys::Action;
+use crate::layout::{Breakpoint, Panel, Screen};
+use crate::mouse::{ChartRegion, Regions};
+use crate::system::cgroups::{CgroupKind, CgroupMonitor, ContainerLimits, Member};
+use crate::system::history::TimeSeries;
+use crate::system::monitor::ProcessColumn;
+use crate::system::services::ServiceMonitor;
+use crate::system::Collector;
+use crate::theme::Theme;
+use chrono::{Local, TimeZone};
+use ratatui::{
+    layout::{Constraint, Direction, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols::{self, Marker},
+    text::{Line, Span},
+    widgets::{
+        Axis, Block, Borders, Cell, Chart, Clear, Dataset, Gauge, GraphType, List, ListItem,
+        Paragraph, Row, Table,
+    },
+    Frame,
+};
+use std::time::Instant;
+
+const NOTICE_SECS: i64 = 10;
+
+/// How a panel is being drawn: whether it has keyboard focus, whether it
+/// is zoomed to full screen, and the terminal size class it is drawn in.
+#[derive(Debug, Clone, Copy, Default)]
+struct PanelView {
+    focused: bool,
+    detailed: bool,
+    size: Breakpoint,
+}
+
+impl PanelView {
+    /// Whether to show the extra detail of the zoomed view, which wide
+    /// terminals have room for anyway.
+    fn expanded(self) -> bool {
+        self.detailed || self.size == Breakpoint::Wide
+    }
+
+    fn compact(self) -> bool {
+        self.size == Breakpoint::Compact && !self.detailed
+    }
+}
+
+pub fn render(f: &mut Frame, app: &App) {
+    *app.regions.borrow_mut() = Regions::default();
+
+    let mut area = f.area();
+    let size = Breakpoint::for_area(area);
+    if let Some(error) = &app.config_error {
+        let banner = Rect::new(area.x, area.y, area.width, 1);
+        render_config_banner(f, banner, app, error);
+        area.y += 1;
+        area.height = area.height.saturating_sub(1);
+    }
+    if let Some(container) = &app.container {
+        let banner = Rect::new(area.x, area.y, area.width, 1);
+        render_container_banner(f, banner, app, container);
+        area.y += 1;
+        area.height = area.height.saturating_sub(1);
+    }
+    if app.alerts.firing().next().is_some() {
+        let banner = Rect::new(area.x, area.y, area.width, 1);
+        render_alert_banner(f, banner, app);
+        area.y += 1;
+        area.height = area.height.saturating_sub(1);
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(area);
+
+    match app.zoomed_panel() {
+        Some(panel) => {
+            let view = PanelView {
+                focused: true,
+                detailed: true,
+                size,
+            };
+            app.regions.borrow_mut().panels.push((panel, chunks[0]));
+            render_panel(f, chunks[0], app, panel, view);
+        }
+        None => {
+            let layout = app.config.screen_layout(app.config.screen);
+            let visible = |p| app.config.panel_visible(p);
+            let placed = if size == Breakpoint::Compact {
+                layout.resolve_compact(chunks[0], visible)
+            } else {
+                layout.resolve(chunks[0], visible)
+            };
+            for (panel, rect) in placed {
+                let view = PanelView {
+                    focused: app.focus == Some(panel),
+                    detailed: false,
+                    size,
+                };
+                app.regions.borrow_mut().panels.push((panel, rect));
+                render_panel(f, rect, app, panel, view);
+            }
+        }
+    }
+    render_footer(f, chunks[1], app, size);
+
+    if app.show_help {
+        render_help(f, chunks[0], app);
+    }
+}
+
+/// Lists every action with its current keys, straight from the keymap.
+fn render_help(f: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.config.theme;
+    let entries = app.keymap.entries();
+
+    let width = 64.min(area.width);
+    let height = (entries.len() as u16 + 3).min(area.height);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let rows: Vec<Row> = entries
+        .iter()
+        .map(|(action, keys)| {
+            let keys = if keys.is_empty() {
+                "-".to_string()
+            } else {
+                keys.iter()
+                    .map(|k| k.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            Row::new(vec![
+                Cell::from(action.description()).style(Style::default().fg(theme.fg())),
+                Cell::from(keys).style(Style::default().fg(theme.primary())),
+            ])
+        })
+        .collect();
+
+    let title = match app.keymap.label(Action::Help) {
+        Some(key) => format!(" Keys ({} to close) ", key),
+        None => " Keys ".to_string(),
+    };
+    let block = panel_block(
+        app,
+        title,
+        PanelView {
+            focused: true,
+            ..PanelView::default()
+        },
+    );
+    let table = Table::new(rows, [Constraint::Length(32), Constraint::Min(10)])
+        .block(block)
+        .style(Style::default().bg(theme.bg()));
+
+    f.render_widget(Clear, popup);
+    f.render_widget(table, popup);
+}
+
+fn render_panel(f: &mut Frame, area: Rect, app: &App, panel: Panel, view: PanelView) {
+    match panel {
+        Panel::System => render_system_info(f, area, app, view),
+        Panel::Cpu => render_cpu(f, area, app, view),
+        Panel::Memory => render_memory(f, area, app, view),
+        Panel::Disks => render_disks(f, area, app, view),
+        Panel::Network => render_network(f, area, app, view),
+        Panel::Alerts => render_alerts(f, area, app, view),
+        Panel::Processes => render_processes(f, area, app, view),
+        Panel::Interfaces => render_interfaces(f, area, app, view),
+        Panel::Sensors => render_sensors(f, area, app, view),
+        Panel::Mounts => render_mounts(f, area, app, view),
+        Panel::Cgroups => render_cgroups(f, area, app, view),
+        Panel::Services => render_services(f, area, app, view),
+    }
+
+    if let Some(collector) = panel_collector(panel) {
+        render_collector_tag(f, area, app, collector);
+    }
+}
+
+/// The collector a panel's data comes from.
+fn panel_collector(panel: Panel) -> Option<Collector> {
+    match panel {
+        Panel::Cpu => Some(Collector::Cpu),
+        Panel::Memory => Some(Collector::Memory),
+        Panel::Disks | Panel::Mounts => Some(Collector::Disks),
+        Panel::Network | Panel::Interfaces => Some(Collector::Network),
+        Panel::Processes => Some(Collector::Processes),
+        Panel::Sensors => Some(Collector::Sensors),
+        Panel::Cgroups => Some(Collector::Cgroups),
+        Panel::Services => Some(Collector::Services),
+        Panel::System | Panel::Alerts => None,
+    }
+}
+
+/// Shows how often a panel's collector samples on the right of its top
+/// border, and how old the data is when the collector has fallen behind.
+fn render_collector_tag(f: &mut Frame, area: Rect, app: &App, collector: Collector) {
+    let theme = &app.config.theme;
+    let mut spans = Vec::new();
+    if let Some(age) = app.displayed().stale_for(collector) {
+        spans.push(Span::styled(
+            format!(" stale {}s ", age.as_secs()),
+            Style::default()
+                .fg(theme.warning())
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    spans.push(Span::styled(
+        format!(
+            " {} ",
+            format_millis(app.scheduler.interval(collector).as_millis() as u64)
+        ),
+        Style::default().fg(theme.border()),
+    ));
+
+    let tag = Line::from(spans);
+    let width = tag.width() as u16;
+    if area.width < width + 4 {
+        return;
+    }
+    f.render_widget(tag, Rect::new(area.right() - width - 2, area.y, width, 1));
+}
+
+fn panel_block<'a>(app: &App, title: String, view: PanelView) -> Block<'a> {
+    let theme = &app.config.theme;
+    let border_type = if app.config.rounded_borders {
+        ratatui::widgets::BorderType::Rounded
+    } else {
+        ratatui::widgets::BorderType::Plain
+    };
+
+    Block::default()
+        .borders(Borders::ALL)
+        .border_type(border_type)
+        .border_style(if view.focused {
+            theme.focused_border()
+        } else {
+            Style::default().fg(theme.border())
+        })
+        .title(Span::styled(title, theme.title()))
+        .style(Style::default().bg(theme.bg()))
+}
+
+fn header_row<'a>(app: &App, titles: &[&'a str]) -> Row<'a> {
+    Row::new(titles.iter().map(|t| Cell::from(*t))).style(app.config.theme.label())
+}
+
+/// The scroll offset of a list panel showing `visible` of `total` rows,
+/// recording how far it can scroll for the mouse handler.
+fn scroll_offset(app: &App, panel: Panel, total: usize, visible: usize) -> usize {
+    let limit = total.saturating_sub(visible);
+    app.regions.borrow_mut().scroll_limits.insert(panel, limit);
+    app.scroll.get(&panel).copied().unwrap_or(0).min(limit)
+}
+
+/// A CPU count that may be fractional, as `cpu.max` quotas often are.
+fn format_cpus(cpus: f64) -> String {
+    let cpus = (cpus * 100.0).round() / 100.0;
+    if cpus == 1.0 {
+        "1 CPU".to_string()
+    } else {
+        format!("{} CPUs", cpus)
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+fn render_processes(f: &mut Frame, area: Rect, app: &App, view: PanelView) {
+    let theme = &app.config.theme;
+    let mut processes = app.displayed().processes.clone();
+    app.process_sort.apply(&mut processes);
+
+    let block = panel_block(app, format!(" Processes: {} ", processes.len()), view);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    // Drop the less important columns when the panel is too narrow for them.
+    let wide = inner.width >= 60;
+    let mut columns = vec![
+        ProcessColumn::Pid,
+        ProcessColumn::Name,
+        ProcessColumn::Cpu,
+        ProcessColumn::Memory,
+    ];
+    let mut headers = vec!["PID", "Name", "CPU%", "Mem MB"];
+    let mut widths = vec![
+        Constraint::Length(8),
+        Constraint::Min(16),
+        Constraint::Length(7),
+        Constraint::Length(10),
+    ];
+    if wide {
+        columns.extend([ProcessColumn::Status, ProcessColumn::Time]);
+        headers.extend(["Status", "Time"]);
+        widths.extend([Constraint::Length(10), Constraint::Length(10)]);
+    }
+    if view.expanded() {
+        headers.push("Command");
+        widths[1] = Constraint::Length(20);
+        widths.push(Constraint::Min(20));
+    }
+
+    // Mark the sort column, and remember where each header cell landed so
+    // clicking it can change the sort. Tables lay out columns like this.
+    let headers: Vec<String> = headers
+        .iter()
+        .enumerate()
+        .map(|(idx, title)| match columns.get(idx) {
+            Some(column) if *column == app.process_sort.column => {
+                format!(
+                    "{}{}",
+                    title,
+                    if app.process_sort.descending {
+                        "▼"
+                    } else {
+                        "▲"
+                    }
+                )
+            }
+            _ => title.to_string(),
+        })
+        .collect();
+    let header_area = Rect::new(inner.x, inner.y, inner.width, 1.min(inner.height));
+    let cells = Layout::horizontal(widths.clone())
+        .flex(Flex::Start)
+        .spacing(1)
+        .split(header_area);
+    app.regions
+        .borrow_mut()
+        .headers
+        .extend(columns.iter().copied().zip(cells.iter().copied()));
+
+    let visible = inner.height.saturating_sub(1) as usize;
+    let offset = scroll_offset(app, Panel::Processes, processes.len(), visible);
+    let rows: Vec<Row> = processes
+        .iter()
+        .skip(offset)
+        .take(visible)
+        .map(|p| {
+            let mut cells = vec![
+                Cell::from(p.pid.to_string()),
+                Cell::from(p.name.clone()),
+                Cell::from(format!("{:.1}", p.cpu_usage)).style(theme.usage(p.cpu_usage)),
+                Cell::from(format!("{:.1}", p.memory_mb())),
+            ];
+            if wide {
+                cells.push(Cell::from(p.status.clone()));
+                cells.push(Cell::from(format_span(p.run_time)));
+            }
+            if view.expanded() {
+                cells.push(
+                    Cell::from(p.command.clone()).style(Style::default().fg(theme.secondary())),
+                );
+            }
+            Row::new(cells).style(theme.value())
+        })
+        .collect();
+
+    let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+    let table = Table::new(rows, widths)
+        .header(header_row(app, &headers))
+        .style(Style::default().bg(theme.bg()));
+
+    f.render_widget(table, inner);
+}
+
+fn render_interfaces(f: &mut Frame, area: Rect, app: &App, view: PanelView) {
+    let block = panel_block(app, " Interfaces ".to_string(), view);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    f.render_widget(interfaces_table(app, Panel::Interfaces, inner), inner);
+}
+
+fn interfaces_table(app: &App, panel: Panel, area: Rect) -> Table<'static> {
+    let theme = &app.config.theme;
+    let interfaces = &app.displayed().network.interfaces;
+    let visible = area.height.saturating_sub(1) as usize;
+    let offset = scroll_offset(app, panel, interfaces.len(), visible);
+    let rows: Vec<Row> = interfaces
+        .iter()
+        .skip(offset)
+        .map(|iface| {
+            Row::new(vec![
+                Cell::from(iface.name.clone()),
+                Cell::from(crate::system::NetworkStats::format_rate(iface.rx_rate))
+                    .style(Style::default().fg(theme.success())),
+                Cell::from(crate::system::NetworkStats::format_rate(iface.tx_rate))
+                    .style(Style::default().fg(theme.danger())),
+                Cell::from(format_bytes(iface.total_received)),
+                Cell::from(format_bytes(iface.total_transmitted)),
+            ])
+            .style(theme.value())
+        })
+        .collect();
+
+    Table::new(
+        rows,
+        [
+            Constraint::Min(12),
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Length(11),
+            Constraint::Length(11),
+        ],
+    )
+    .header(header_row(
+        app,
+        &["Interface", "↓ Rate", "↑ Rate", "Total RX", "Total TX"],
+    ))
+    .style(Style::default().bg(theme.bg()))
+}
+
+fn render_sensors(f: &mut Frame, area: Rect, app: &App, view: PanelView) {
+    let theme = &app.config.theme;
+    let sensors = &app.displayed().sensors;
+
+    let block = panel_block(app, " Sensors ".to_string(), view);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if sensors.is_empty() {
+        let empty = Paragraph::new(Span::styled(
+            "No temperature sensors found",
+            Style::default().fg(theme.border()),
+        ))
+        .style(Style::default().bg(theme.bg()));
+        f.render_widget(empty, inner);
+        return;
+    }
+
+    let offset = scroll_offset(
+        app,
+        Panel::Sensors,
+        sensors.len(),
+        inner.height.saturating_sub(1) as usize,
+    );
+    let rows: Vec<Row> = sensors
+        .iter()
+        .skip(offset)
+        .map(|sensor| {
+            let limit = sensor.critical.unwrap_or(100.0).max(1.0);
+            let usage = theme.usage(sensor.temperature / limit * 100.0);
+            Row::new(vec![
+                Cell::from(sensor.label.clone()),
+                Cell::from(app.config.format_temp(sensor.temperature)).style(usage),
+                Cell::from(app.config.format_temp(sensor.max)),
+                Cell::from(
+                    sensor
+                        .critical
+                        .map(|c| app.config.format_temp(c))
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+            ])
+            .style(theme.value())
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(16),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(10),
+        ],
+    )
+    .header(header_row(app, &["Sensor", "Temp", "Max", "Critical"]))
+    .style(Style::default().bg(theme.bg()));
+
+    f.render_widget(table, inner);
+}
+
+fn render_mounts(f: &mut Frame, area: Rect, app: &App, view: PanelView) {
+    let theme = &app.config.theme;
+    let disks = &app.displayed().disks;
+
+    let block = panel_block(app, " Mounts ".to_string(), view);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let offset = scroll_offset(
+        app,
+        Panel::Mounts,
+        disks.len(),
+        inner.height.saturating_sub(1) as usize,
+    );
+    let rows: Vec<Row> = disks
+        .iter()
+        .skip(offset)
+        .map(|disk| {
+            Row::new(vec![
+                Cell::from(disk.mount_point.clone()),
+                Cell::from(disk.name.clone()),
+                Cell::from(disk.file_system.clone()),
+                Cell::from(disk.disk_type.clone()),
+                Cell::from(format_bytes(disk.used())),
+                Cell::from(format_bytes(disk.total)),
+                Cell::from(format!("{:.1}%", disk.used_percent()))
+                    .style(theme.usage(disk.used_percent())),
+            ])
+            .style(theme.value())
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(12),
+            Constraint::Min(12),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(7),
+        ],
+    )
+    .header(header_row(
+        app,
+        &["Mount", "Device", "FS", "Type", "Used", "Total", "Use%"],
+    ))
+    .style(Style::default().bg(theme.bg()));
+
+    f.render_widget(table, inner);
+}
+
+fn render_cgroups(f: &mut Frame, area: Rect, app: &App, view: PanelView) {
+    let theme = &app.config.theme;
+    let cgroups = &app.displayed().cgroups;
+
+    let block = panel_block(app, format!(" Cgroups: {} ", cgroups.len()), view);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if cgroups.is_empty() {
+        let message = if CgroupMonitor::available() {
+            "No cgroups with processes found"
+        } else {
+            "No cgroup v2 hierarchy at /sys/fs/cgroup"
+        };
+        let empty = Paragraph::new(Span::styled(message, Style::default().fg(theme.border())))
+            .style(Style::default().bg(theme.bg()));
+        f.render_widget(empty, inner);
+        return;
+    }
+
+    let wide = inner.width >= 90 || view.expanded();
+    let offset = scroll_offset(
+        app,
+        Panel::Cgroups,
+        cgroups.len(),
+        inner.height.saturating_sub(1) as usize,
+    );
+    let rows: Vec<Row> = cgroups
+        .iter()
+        .skip(offset)
+        .map(|cgroup| {
+            // Indented under its parent, so slices group their services and containers.
+            let name = format!("{}{}", "  ".repeat(cgroup.depth - 1), cgroup.name);
+            let name_style = match cgroup.kind {
+                CgroupKind::Container(_) => Style::default().fg(theme.primary()),
+                CgroupKind::Slice => Style::default().fg(theme.secondary()),
+                _ => theme.value(),
+            };
+            let memory_style = cgroup
+                .memory_percent()
+                .map(|p| theme.usage(p))
+                .unwrap_or_default();
+            let mut cells = vec![
+                Cell::from(name).style(name_style),
+                Cell::from(format!("{:.1}", cgroup.cpu_usage)).style(theme.usage(cgroup.cpu_usage)),
+                Cell::from(format_bytes(cgroup.memory_current)).style(memory_style),
+                Cell::from(
+                    cgroup
+                        .memory_max
+                        .map(format_bytes)
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+                Cell::from(match cgroup.pids_max {
+                    Some(max) => format!("{}/{}", cgroup.pids_current, max),
+                    None => cgroup.pids_current.to_string(),
+                }),
+            ];
+            if wide {
+                cells.push(Cell::from(crate::system::NetworkStats::format_rate(
+                    cgroup.io_read_rate,
+                )));
+                cells.push(Cell::from(crate::system::NetworkStats::format_rate(
+                    cgroup.io_write_rate,
+                )));
+            }
+            cells.push(Cell::from(member_summary(
+                &cgroup.processes,
+                view.expanded(),
+            )));
+            if view.expanded() {
+                cells.push(
+                    Cell::from(cgroup.path.clone()).style(Style::default().fg(theme.secondary())),
+                );
+            }
+            Row::new(cells).style(theme.value())
+        })
+        .collect();
+
+    let mut headers = vec!["Cgroup", "CPU%", "Memory", "Limit", "PIDs"];
+    let mut widths = vec![
+        Constraint::Min(24),
+        Constraint::Length(7),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(11),
+    ];
+    if wide {
+        headers.extend(["IO read", "IO write"]);
+        widths.extend([Constraint::Length(12), Constraint::Length(12)]);
+    }
+    headers.push("Processes");
+    widths.push(Constraint::Min(16));
+    if view.expanded() {
+        headers.push("Path");
+        widths.push(Constraint::Min(30));
+    }
+
+    let table = Table::new(rows, widths)
+        .header(header_row(app, &headers))
+        .style(Style::default().bg(theme.bg()));
+
+    f.render_widget(table, inner);
+}
+
+fn render_services(f: &mut Frame, area: Rect, app: &App, view: PanelView) {
+    let theme = &app.config.theme;
+    let services = &app.displayed().services;
+
+    let failed = services.iter().filter(|service| service.failed()).count();
+    let title = match failed {
+        0 => format!(" Services: {} ", services.len()),
+        n => format!(" Services: {} ({} failed) ", services.len(), n),
+    };
+    let block = panel_block(app, title, view);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if services.is_empty() {
+        let message = if ServiceMonitor::systemd_running() || ServiceMonitor::cgroups_available() {
+            "No running or failed services"
+        } else {
+            "No systemd services: systemd isn't running and there's no system.slice cgroup"
+        };
+        let empty = Paragraph::new(Span::styled(message, Style::default().fg(theme.border())))
+            .style(Style::default().bg(theme.bg()));
+        f.render_widget(empty, inner);
+        return;
+    }
+
+    let wide = inner.width >= 90 || view.expanded();
+    let offset = scroll_offset(
+        app,
+        Panel::Services,
+        services.len(),
+        inner.height.saturating_sub(1) as usize,
+    );
+    let rows: Vec<Row> = services
+        .iter()
+        .skip(offset)
+        .map(|service| {
+            let state = if service.sub_state.is_empty() {
+                service.active_state.clone()
+            } else {
+                format!("{} ({})", service.active_state, service.sub_state)
+            };
+            let state_style = match service.active_state.as_str() {
+                "active" => Style::default().fg(theme.success()),
+                "failed" => Style::default().fg(theme.danger()),
+                _ => Style::default().fg(theme.warning()),
+            };
+            let mut cells = vec![
+                Cell::from(service.unit.clone()),
+                Cell::from(state).style(state_style),
+                Cell::from(
+                    service
+                        .main_pid
+                        .map(|pid| pid.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+                Cell::from(format!("{:.1}", service.cpu_usage))
+                    .style(theme.usage(service.cpu_usage)),
+                Cell::from(
+                    service
+                        .memory
+                        .map(format_bytes)
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+            ];
+            if wide {
+                cells.push(
+                    Cell::from(service.description.clone())
+                        .style(Style::default().fg(theme.secondary())),
+                );
+            }
+            let row_style = if service.failed() {
+                Style::default()
+                    .fg(theme.danger())
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                theme.value()
+            };
+            Row::new(cells).style(row_style)
+        })
+        .collect();
+
+    let mut headers = vec!["Service", "State", "PID", "CPU%", "Memory"];
+    let mut widths = vec![
+        Constraint::Min(24),
+        Constraint::Length(20),
+        Constraint::Length(8),
+        Constraint::Length(7),
+        Constraint::Length(10),
+    ];
+    if wide {
+        headers.push("Description");
+        widths.push(Constraint::Min(20));
+    }
+
+    let table = Table::new(rows, widths)
+        .header(header_row(app, &headers))
+        .style(Style::default().bg(theme.bg()));
+
+    f.render_widget(table, inner);
+}
+
+/// The processes of a cgroup by name, such as `nginx ×4, sh`, or with their
+/// PIDs when there's room: `nginx (812 813), sh (900)`.
+fn member_summary(members: &[Member], pids: bool) -> String {
+    let mut groups: Vec<(&str, Vec<u32>)> = Vec::new();
+    for member in members {
+        match groups.iter_mut().find(|(name, _)| *name == member.name) {
+            Some((_, group)) => group.push(member.pid),
+            None => groups.push((&member.name, vec![member.pid])),
+        }
+    }
+    groups
+        .iter()
+        .map(|(name, group)| match (pids, group.len()) {
+            (true, _) => {
+                let list: Vec<String> = group.iter().map(|pid| pid.to_string()).collect();
+                format!("{} ({})", name, list.join(" "))
+            }
+            (false, 1) => name.to_string(),
+            (false, n) => format!("{} ×{}", name, n),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn render_config_banner(f: &mut Frame, area: Rect, app: &App, error: &str) {
+    let theme = &app.config.theme;
+    let banner_style = theme.badge(theme.warning());
+    let line = Line::from(vec![
+        Span::styled(" ⚠ CONFIG ", banner_style.add_modifier(Modifier::BOLD)),
+        Span::styled(
+            format!("{} (still using the previous settings)", error),
+            banner_style,
+        ),
+    ]);
+    f.render_widget(Paragraph::new(line).style(banner_style), area);
+}
+
+fn render_container_banner(f: &mut Frame, area: Rect, app: &App, container: &ContainerLimits) {
+    let theme = &app.config.theme;
+    let banner_style = theme.badge(theme.primary());
+    let text = if !container.readable() {
+        "no cgroup v2 limits to read; showing host values".to_string()
+    } else if app.config.host_values {
+        "showing host values (host_values = false for the container's)".to_string()
+    } else {
+        let cpus = container
+            .cpus
+            .map(format_cpus)
+            .unwrap_or_else(|| "all CPUs".to_string());
+        let memory = container
+            .memory_max
+            .map(format_bytes)
+            .unwrap_or_else(|| "unlimited memory".to_string());
+        format!(
+            "usage against limits of {} and {} (host_values = true for the host's)",
+            cpus, memory
+        )
+    };
+    let line = Line::from(vec![
+        Span::styled(
+            " ▣ CONTAINER MODE ",
+            banner_style.add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(format!("{}: {}", container.runtime, text), banner_style),
+    ]);
+    f.render_widget(Paragraph::new(line).style(banner_style), area);
+}
+
+fn render_alert_banner(f: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.config.theme;
+    let now = Instant::now();
+    let banner_style = theme.alert_banner();
+
+    let mut spans = vec![Span::styled(
+        " ⚠ ALERT ",
+        banner_style.add_modifier(Modifier::BOLD),
+    )];
+    for (idx, alert) in app.alerts.firing().enumerate() {
+        if idx > 0 {
+            spans.push(Span::styled(" · ", banner_style));
+        }
+        let value = alert
+            .value
+            .map(|v| format!(" {:.1}{}", v, alert.rule.condition.metric.unit()))
+            .unwrap_or_default();
+        spans.push(Span::styled(
+            alert.rule.name.clone(),
+            banner_style.add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::styled(
+            format!("{} ({})", value, format_span(alert.duration(now).as_secs())),
+            banner_style,
+        ));
+    }
+
+    f.render_widget(Paragraph::new(Line::from(spans)).style(banner_style), area);
+}
+
+fn render_system_info(f: &mut Frame, area: Rect, app: &App, view: PanelView) {
+    let theme = &app.config.theme;
+    let info = &app.system_info;
+
+    let block = panel_block(app, " System Information ".to_string(), view);
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    // Small terminals get the essentials on a couple of lines, without the dog.
+    if view.compact() {
+        let label = theme.label();
+        let value = theme.value();
+        let lines = vec![
+            Line::from(vec![
+                Span::styled(format!("{}@{} ", info.username, info.hostname), label),
+                Span::styled(
+                    format!("{} • {}", info.distribution, info.kernel_version),
+                    value,
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Up ", label),
+                Span::styled(info.uptime_string(), value),
+                Span::styled(" • ", value),
+                Span::styled(
+                    format!("{} cores • {:.1} GB", info.cpu_cores, info.memory_gb()),
+                    value,
+                ),
+            ]),
+        ];
+        f.render_widget(
+            Paragraph::new(lines).style(Style::default().bg(theme.bg())),
+            inner,
+        );
+        return;
+    }
+
+    let main_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(12), Constraint::Min(30)])
+        .split(inner);
+
+    let dog_art = vec![
+        Line::from(""),
+        Line::from(Span::styled("   ʕ•ᴥ•ʔ", theme.label())),
+        Line::from(Span::styled(
+            "  ∪￣￣∪",
+            Style::default().fg(theme.secondary()),
+        )),
+        Line::from(""),
+    ];
+
+    let dog_paragraph = Paragraph::new(dog_art)
+        .style(Style::default().bg(theme.bg()))
+        .alignment(ratatui::layout::Alignment::Center);
+
+    f.render_widget(dog_paragraph, main_chunks[0]);
+
+    let info_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(main_chunks[1]);
+
+    let mut left_info = vec![
+        Line::from(vec![
+            Span::styled("User: ", theme.label()),
+            Span::styled(
+                format!("{}@{}", info.username, info.hostname),
+                theme.value(),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("OS: ", theme.label()),
+            Span::styled(&info.os_name, theme.value()),
+        ]),
+        Line::from(vec![
+            Span::styled("Distro: ", theme.label()),
+            Span::styled(&info.distribution, theme.value()),
+        ]),
+        Line::from(vec![
+            Span::styled("Kernel: ", theme.label()),
+            Span::styled(&info.kernel_version, theme.value()),
+        ]),
+        Line::from(vec![
+            Span::styled("Arch: ", theme.label()),
+            Span::styled(&info.architecture, theme.value()),
+        ]),
+        Line::from(vec![
+            Span::styled("Shell: ", theme.label()),
+            Span::styled(&info.shell, theme.value()),
+        ]),
+        Line::from(vec![
+            Span::styled("Processes: ", theme.label()),
+            Span::styled(format!("{}", info.processes_count), theme.value()),
+        ]),
+        Line::from(vec![
+            Span::styled("Boot Time: ", theme.label()),
+            Span::styled(&info.boot_time, theme.value()),
+        ]),
+    ];
+
+    if view.expanded() {
+        left_info.insert(
+            2,
+            Line::from(vec![
+                Span::styled("Version: ", theme.label()),
+                Span::styled(&info.os_version, theme.value()),
+            ]),
+        );
+    }
+
+    let mut right_info = vec![
+        Line::from(vec![
+            Span::styled("CPU: ", theme.label()),
+            Span::styled(&info.cpu_name, theme.value()),
+        ]),
+        Line::from(vec![
+            Span::styled("Cores: ", theme.label()),
+            Span::styled(
+                format!("{} ({} physical)", info.cpu_cores, info.cpu_physical_cores),
+                theme.value(),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Frequency: ", theme.label()),
+            Span::styled(&info.cpu_frequency, theme.value()),
+        ]),
+        Line::from(vec![
+            Span::styled("Memory: ", theme.label()),
+            Span::styled(format!("{:.1} GB", info.memory_gb()), theme.value()),
+        ]),
+        Line::from(vec![
+            Span::styled("Uptime: ", theme.label()),
+            Span::styled(info.uptime_string(), theme.value()),
+        ]),
+    ];
+
+    if info.display_info.resolution != "Unknown" && info.display_info.resolution != "N/A" {
+        right_info.push(Line::from(vec![
+            Span::styled("Display: ", theme.label()),
+            Span::styled(
+                format!(
+                    "{} @ {}",
+                    info.display_info.resolution, info.display_info.refresh_rate
+                ),
+                theme.value(),
+            ),
+        ]));
+    }
+
+    if info.audio_info.default_device != "Unknown" && info.audio_info.default_device != "N/A" {
+        let audio_device = if info.audio_info.default_device.len() > 35 {
+            format!("{}...", &info.audio_info.default_device[..32])
+        } else {
+            info.audio_info.default_device.clone()
+        };
+        right_info.push(Line::from(vec![
+            Span::styled("Audio: ", theme.label()),
+            Span::styled(audio_device, theme.value()),
+        ]));
+    }
+
+    let left_paragraph = Paragraph::new(left_info).style(Style::default().bg(theme.bg()));
+    let right_paragraph = Paragraph::new(right_info).style(Style::default().bg(theme.bg()));
+
+    f.render_widget(left_paragraph, info_chunks[0]);
+    f.render_widget(right_paragraph, info_chunks[1]);
+}
+
+fn render_cpu(f: &mut Frame, area: Rect, app: &App, view: PanelView) {
+    let theme = &app.config.theme;
+    let cpu_stats = &app.displayed().cpu;
+
+    let limit = app
+        .container_view()
+        .and_then(|container| container.cpus)
+        .map(|cpus| format!("of {} ", format_cpus(cpus)))
+        .unwrap_or_default();
+    let title = if view.compact() {
+        format!(" CPU {:.1}% ", cpu_stats.global_usage)
+    } else {
+        format!(" CPU Usage: {:.1}% {}", cpu_stats.global_usage, limit)
+    };
+    let block = panel_block(app, title, view);
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let inner = if view.expanded() && !cpu_stats.per_core.is_empty() {
+        // Lay many cores out as a grid rather than a list running off the panel.
+        let rows = inner.height.saturating_sub(2).max(1) as usize;
+        let grid_columns = cpu_stats
+            .per_core
+            .len()
+            .div_ceil(rows)
+            .min((inner.width / 64).max(1) as usize);
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Min(30),
+                Constraint::Length(32 * grid_columns as u16),
+            ])
+            .split(inner);
+        render_per_core(f, columns[1], app, &cpu_stats.per_core);
+        columns[0]
+    } else {
+        inner
+    };
+
+    let cpu_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(3)])
+        .margin(if view.compact() { 0 } else { 1 })
+        .split(inner);
+
+    let gauge_color = theme.usage_color(cpu_stats.global_usage);
+    render_gauge(
+        f,
+        cpu_chunks[0],
+        theme,
+        cpu_stats.global_usage,
+        format!("{:.1}%", cpu_stats.global_usage),
+    );
+
+    render_history_chart(
+        f,
+        cpu_chunks[1],
+        app,
+        &app.history.cpu,
+        100.0,
+        gauge_color,
+        |v| format!("{:.1}%", v),
+    );
+}
+
+fn render_per_core(f: &mut Frame, area: Rect, app: &App, per_core: &[f32]) {
+    let theme = &app.config.theme;
+    let area = area.inner(ratatui::layout::Margin::new(0, 1));
+    let rows = area.height.max(1) as usize;
+    let grid_columns = per_core.len().div_ceil(rows).max(1);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Fill(1); grid_columns])
+        .split(area);
+
+    for (grid_column, (column, cores)) in columns.iter().zip(per_core.chunks(rows)).enumerate() {
+        let first = grid_column * rows;
+        let bar_width = column.width.saturating_sub(16) as usize;
+        let items: Vec<ListItem> = cores
+            .iter()
+            .enumerate()
+            .map(|(idx, usage)| {
+                let mut spans = vec![Span::styled(
+                    format!("cpu{:<3} ", first + idx),
+                    Style::default().fg(theme.secondary()),
+                )];
+                spans.extend(usage_bar(theme, *usage, bar_width));
+                spans.push(Span::styled(
+                    format!(" {:>5.1}%{}", usage, theme.usage_marker(*usage)),
+                    theme.value(),
+                ));
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        f.render_widget(
+            List::new(items).style(Style::default().bg(theme.bg())),
+            *column,
+        );
+    }
+}
+
+/// A gauge filled to `percent`. Themes with a gradient shade each cell by the
+/// usage it stands for, so a high reading runs through every color.
+fn render_gauge(f: &mut Frame, area: Rect, theme: &Theme, percent: f32, label: String) {
+    let ratio = (percent as f64 / 100.0).clamp(0.0, 1.0);
+    let label = format!("{}{}", label, theme.usage_marker(percent));
+    f.render_widget(
+        Gauge::default()
+            .gauge_style(theme.gauge(percent))
+            .ratio(ratio)
+            .label(label),
+        area,
+    );
+
+    if !theme.gradient || area.width == 0 {
+        return;
+    }
+    let filled = (area.width as f64 * ratio).round() as u16;
+    for x in area.left()..area.left() + filled {
+        let color = theme.usage_color((x - area.left()) as f32 / area.width as f32 * 100.0);
+        for y in area.top()..area.bottom() {
+            if let Some(cell) = f.buffer_mut().cell_mut((x, y)) {
+                // Gauges swap colors under their label.
+                if cell.symbol() == symbols::block::FULL {
+                    cell.set_fg(color);
+                } else {
+                    cell.set_bg(color);
+                }
+            }
+        }
+    }
+}
+
+/// A bar of `width` cells filled to `percent`, shaded like `render_gauge`.
+fn usage_bar(theme: &Theme, percent: f32, width: usize) -> Vec<Span<'static>> {
+    let filled = (((percent / 100.0) * width as f32) as usize).min(width);
+    let empty = Span::styled("░".repeat(width - filled), theme.gauge(percent));
+    if !theme.gradient {
+        return vec![
+            Span::styled("█".repeat(filled), theme.gauge(percent)),
+            empty,
+        ];
+    }
+    let mut spans: Vec<Span> = (0..filled)
+        .map(|cell| Span::styled("█", theme.gauge(cell as f32 / width as f32 * 100.0)))
+        .collect();
+    spans.push(empty);
+    spans
+}
+
+fn render_history_chart(
+    f: &mut Frame,
+    area: Rect,
+    app: &App,
+    series: &TimeSeries,
+    max: f64,
+    color: Color,
+    format_value: fn(f32) -> String,
+) {
+    let theme = &app.config.theme;
+    let view = &app.chart;
+    let window = view.window();
+
+    let newest = app.chart_newest(series);
+    let end = newest.saturating_sub(view.offset);
+    let start = end.saturating_sub(window);
+
+    let points: Vec<(f64, f64)> = series
+        .range(end, window)
+        .iter()
+        .map(|s| ((s.timestamp - start) as f64, s.value as f64))
+        .collect();
+
+    let time_format = if window >= 3_600 { "%H:%M" } else { "%H:%M:%S" };
+    let label_style = Style::default().fg(theme.border());
+    let x_labels = vec![
+        Span::styled(format_timestamp(start, time_format), label_style),
+        Span::styled(
+            format_timestamp(start + window / 2, time_format),
+            label_style,
+        ),
+        Span::styled(format_timestamp(end, time_format), label_style),
+    ];
+    let y_labels = vec![
+        Span::styled(format_value(0.0), label_style),
+        Span::styled(format_value((max / 2.0) as f32), label_style),
+        Span::styled(format_value(max as f32), label_style),
+    ];
+
+    let mut status = vec![Span::styled(
+        format!("[{}] ", view.window_label()),
+        Style::default()
+            .fg(theme.primary())
+            .add_modifier(Modifier::BOLD),
+    )];
+    if view.is_live() && app.paused.is_some() {
+        status.push(Span::styled("paused", Style::default().fg(theme.warning())));
+    } else if view.is_live() {
+        status.push(Span::styled("live", Style::default().fg(theme.success())));
+    } else {
+        status.push(Span::styled(
+            format!("-{}", format_span(view.offset)),
+            Style::default().fg(theme.warning()),
+        ));
+    }
+
+    let cursor_line;
+    let mut datasets = vec![Dataset::default()
+        .marker(Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(theme.chart(color))
+        .data(&points)];
+
+    if let Some(cursor) = view.cursor {
+        let x = window.saturating_sub(cursor) as f64;
+        cursor_line = [(x, 0.0), (x, max)];
+        datasets.push(
+            Dataset::default()
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(theme.fg()))
+                .data(&cursor_line),
+        );
+
+        let nearest = points
+            .iter()
+            .min_by(|a, b| (a.0 - x).abs().total_cmp(&(b.0 - x).abs()));
+        let readout = match nearest {
+            Some(&(px, value)) => format!(
+                "  {}  {}",
+                format_timestamp(start + px as u64, "%Y-%m-%d %H:%M:%S"),
+                format_value(value as f32)
+            ),
+            None => "  no data".to_string(),
+        };
+        status.push(Span::styled(readout, theme.value()));
+    }
+
+    let chart_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(area);
+
+    let graph = chart_graph_area(chart_chunks[0], &y_labels, &x_labels[0]);
+    app.regions.borrow_mut().charts.push(ChartRegion {
+        graph,
+        start,
+        window,
+        newest,
+    });
+
+    let chart = Chart::new(datasets)
+        .style(Style::default().bg(theme.bg()))
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.border()))
+                .bounds([0.0, window as f64])
+                .labels(x_labels),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.border()))
+                .bounds([0.0, max])
+                .labels(y_labels),
+        );
+
+    f.render_widget(chart, chart_chunks[0]);
+
+    // Shade the range being selected with the mouse.
+    if let Some(drag) = app.drag.filter(|d| d.chart.graph == graph) {
+        let (left, right) = drag.columns();
+        for x in left..=right {
+            for y in graph.top()..graph.bottom() {
+                if let Some(cell) = f.buffer_mut().cell_mut((x, y)) {
+                    cell.set_style(theme.shade());
+                }
+            }
+        }
+    }
+    f.render_widget(
+        Paragraph::new(Line::from(status)).style(Style::default().bg(theme.bg())),
+        chart_chunks[1],
+    );
+}
+
+/// Where a chart draws its plot inside `area`: right of the y-axis labels
+/// (or the overhang of the first x label) and the axis line, above the x
+/// axis and its labels. This follows ratatui's own chart layout.
+fn chart_graph_area(area: Rect, y_labels: &[Span], first_x_label: &Span) -> Rect {
+    let label_width = y_labels
+        .iter()
+        .map(Span::width)
+        .max()
+        .unwrap_or(0)
+        .max(first_x_label.width().saturating_sub(1)) as u16;
+    let left = label_width.min(area.width / 3) + 1;
+    Rect::new(
+        area.x + left,
+        area.y,
+        area.width.saturating_sub(left),
+        area.height.saturating_sub(2),
+    )
+}
+
+fn format_timestamp(timestamp: u64, format: &str) -> String {
+    Local
+        .timestamp_opt(timestamp as i64, 0)
+        .single()
+        .map(|dt| dt.format(format).to_string())
+        .unwrap_or_default()
+}
+
+fn render_memory(f: &mut Frame, area: Rect, app: &App, view: PanelView) {
+    let theme = &app.config.theme;
+    let mem_stats = &app.displayed().memory;
+
+    let block = panel_block(
+        app,
+        format!(" Memory: {:.1}% ", mem_stats.used_percent()),
+        view,
+    );
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let ram_color = theme.usage_color(mem_stats.used_percent());
+    let ram_available = mem_stats.total_gb() - mem_stats.used_gb();
+    let margin = if view.compact() { 0 } else { 1 };
+    let usage_text = |used: f64, free: f64, percent: f32| {
+        if view.compact() {
+            format!("{:.1}/{:.1}G {:.0}%", used, used + free, percent)
+        } else {
+            format!(
+                "{:.1} GB used • {:.1} GB free • {:.1}%",
+                used, free, percent
+            )
+        }
+    };
+
+    let inner = if view.expanded() {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(7), Constraint::Min(5)])
+            .split(inner);
+        let chart_area = rows[1].inner(ratatui::layout::Margin::new(1, 0));
+        render_history_chart(
+            f,
+            chart_area,
+            app,
+            &app.history.memory,
+            100.0,
+            ram_color,
+            |v| format!("{:.0}%", v),
+        );
+        rows[0]
+    } else {
+        inner
+    };
+
+    if mem_stats.swap_total > 0 {
+        let mem_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Length(margin),
+                Constraint::Length(2),
+            ])
+            .horizontal_margin(1)
+            .vertical_margin(margin)
+            .split(inner);
+
+        let ram_info = vec![Line::from(vec![
+            Span::styled("RAM: ", theme.label()),
+            Span::styled(
+                usage_text(mem_stats.used_gb(), ram_available, mem_stats.used_percent()),
+                theme.value(),
+            ),
+        ])];
+
+        let ram_paragraph = Paragraph::new(ram_info).style(Style::default().bg(theme.bg()));
+        f.render_widget(ram_paragraph, mem_chunks[0]);
+
+        render_gauge(
+            f,
+            Rect::new(mem_chunks[0].x, mem_chunks[0].y + 1, mem_chunks[0].width, 1),
+            theme,
+            mem_stats.used_percent(),
+            String::new(),
+        );
+
+        let swap_available = mem_stats.swap_total_gb() - mem_stats.swap_used_gb();
+
+        let swap_info = vec![Line::from(vec![
+            Span::styled("Swap: ", theme.label()),
+            Span::styled(
+                usage_text(
+                    mem_stats.swap_used_gb(),
+                    swap_available,
+                    mem_stats.swap_percent(),
+                ),
+                theme.value(),
+            ),
+        ])];
+
+        let swap_paragraph = Paragraph::new(swap_info).style(Style::default().bg(theme.bg()));
+        f.render_widget(swap_paragraph, mem_chunks[2]);
+
+        render_gauge(
+            f,
+            Rect::new(mem_chunks[2].x, mem_chunks[2].y + 1, mem_chunks[2].width, 1),
+            theme,
+            mem_stats.swap_percent(),
+            String::new(),
+        );
+    } else {
+        let mem_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(2)])
+            .horizontal_margin(1)
+            .vertical_margin(margin)
+            .split(inner);
+
+        let ram_info = vec![Line::from(vec![
+            Span::styled("RAM: ", theme.label()),
+            Span::styled(
+                usage_text(mem_stats.used_gb(), ram_available, mem_stats.used_percent()),
+                theme.value(),
+            ),
+        ])];
+
+        let ram_paragraph = Paragraph::new(ram_info).style(Style::default().bg(theme.bg()));
+        f.render_widget(ram_paragraph, mem_chunks[0]);
+
+        render_gauge(
+            f,
+            Rect::new(mem_chunks[0].x, mem_chunks[0].y + 1, mem_chunks[0].width, 1),
+            theme,
+            mem_stats.used_percent(),
+            String::new(),
+        );
+    }
+}
+
+fn render_disks(f: &mut Frame, area: Rect, app: &App, view: PanelView) {
+    let theme = &app.config.theme;
+    let disk_stats = &app.displayed().disks;
+
+    let block = panel_block(app, " Disk Usage ".to_string(), view);
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let bar_width: usize = if view.expanded() {
+        // Leave room for the device name, sizes and mount details.
+        (inner.width.saturating_sub(90) as usize).clamp(10, 60)
+    } else if view.compact() {
+        (inner.width.saturating_sub(28) as usize).clamp(5, 15)
+    } else {
+        15
+    };
+
+    let offset = scroll_offset(app, Panel::Disks, disk_stats.len(), inner.height as usize);
+    let disk_items: Vec<ListItem> = disk_stats
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .map(|(idx, disk)| {
+            let disk_label = if disk.name.is_empty() {
+                format!("HD{}", idx + 1)
+            } else if view.expanded() {
+                disk.name.clone()
+            } else {
+                let name = disk.name.replace("\\", "").replace(".", "");
+                if name.len() > 4 {
+                    name[..4].to_string()
+                } else {
+                    name
+                }
+            };
+
+            let mut spans = vec![
+                Span::styled(format!("{:<4} ", disk_label), theme.label()),
+                Span::styled(
+                    format!("[{}] ", disk.disk_type),
+                    Style::default().fg(theme.primary()),
+                ),
+            ];
+            spans.extend(usage_bar(theme, disk.used_percent(), bar_width));
+            spans.push(Span::styled(
+                format!(
+                    " {:.0}/{:.0}GB{}",
+                    disk.used_gb(),
+                    disk.total_gb(),
+                    theme.usage_marker(disk.used_percent())
+                ),
+                theme.value(),
+            ));
+
+            if view.expanded() {
+                spans.push(Span::styled(
+                    format!(
+                        " {:.1}% • {:.0}GB free • {} on {}",
+                        disk.used_percent(),
+                        disk.available_gb(),
+                        disk.file_system,
+                        disk.mount_point
+                    ),
+                    Style::default().fg(theme.secondary()),
+                ));
+            }
+
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(disk_items).style(Style::default().bg(theme.bg()));
+
+    f.render_widget(list, inner);
+}
+
+fn render_network(f: &mut Frame, area: Rect, app: &App, view: PanelView) {
+    let theme = &app.config.theme;
+    let network = &app.displayed().network;
+
+    let block = panel_block(app, " Network ".to_string(), view);
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rx = crate::system::NetworkStats::format_rate(network.rx_rate_mbps() * 1_024.0 * 1_024.0);
+    let tx = crate::system::NetworkStats::format_rate(network.tx_rate_mbps() * 1_024.0 * 1_024.0);
+    if view.compact() {
+        let lines = vec![
+            Line::from(vec![
+                Span::styled(
+                    "↓ ",
+                    Style::default()
+                        .fg(theme.success())
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!("{:<12}", rx), theme.value()),
+                Span::styled(
+                    "↑ ",
+                    Style::default()
+                        .fg(theme.danger())
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(tx, theme.value()),
+            ]),
+            Line::from(Span::styled(
+                format!(
+                    "RX {:.2} GB  TX {:.2} GB",
+                    network.total_received_gb(),
+                    network.total_transmitted_gb()
+                ),
+                Style::default().fg(theme.secondary()),
+            )),
+        ];
+        f.render_widget(
+            Paragraph::new(lines).style(Style::default().bg(theme.bg())),
+            inner,
+        );
+        return;
+    }
+
+    let network_info = vec![
+        Line::from(vec![
+            Span::styled(
+                "↓ Download: ",
+                Style::default()
+                    .fg(theme.success())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(rx, theme.value()),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "↑ Upload: ",
+                Style::default()
+                    .fg(theme.danger())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(tx, theme.value()),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Total RX: ", Style::default().fg(theme.secondary())),
+            Span::styled(
+                format!("{:.2} GB", network.total_received_gb()),
+                theme.value(),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Total TX: ", Style::default().fg(theme.secondary())),
+            Span::styled(
+                format!("{:.2} GB", network.total_transmitted_gb()),
+                theme.value(),
+            ),
+        ]),
+    ];
+
+    let paragraph = Paragraph::new(network_info)
+        .style(Style::default().bg(theme.bg()))
+        .block(Block::default());
+
+    if view.expanded() {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(6), Constraint::Min(3)])
+            .split(inner);
+        f.render_widget(paragraph, rows[0]);
+        f.render_widget(interfaces_table(app, Panel::Network, rows[1]), rows[1]);
+    } else {
+        f.render_widget(paragraph, inner);
+    }
+}
+
+fn render_alerts(f: &mut Frame, area: Rect, app: &App, view: PanelView) {
+    let theme = &app.config.theme;
+    let now = Instant::now();
+
+    let firing = app.alerts.firing().count();
+    let block = panel_block(app, format!(" Alerts: {} firing ", firing), view);
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let (list_area, log_area) = if view.expanded() {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Fill(1), Constraint::Fill(1)])
+            .split(inner);
+        (rows[0], Some(rows[1]))
+    } else {
+        (inner, None)
+    };
+
+    let offset = scroll_offset(
+        app,
+        Panel::Alerts,
+        app.alerts.alerts.len(),
+        list_area.height as usize,
+    );
+    let mut items: Vec<ListItem> = app
+        .alerts
+        .alerts
+        .iter()
+        .skip(offset)
+        .map(|alert| {
+            let (label, color) = match alert.state {
+                AlertState::Inactive => ("ok", theme.success()),
+                AlertState::Pending { .. } => ("pending", theme.warning()),
+                AlertState::Firing { .. } => ("firing", theme.danger()),
+                AlertState::Resolved { .. } => ("resolved", theme.secondary()),
+            };
+            let duration = match alert.state {
+                AlertState::Inactive => String::new(),
+                _ => format!(" {}", format_span(alert.duration(now).as_secs())),
+            };
+            let value = alert
+                .value
+                .map(|v| format!("{:.1}{}", v, alert.rule.condition.metric.unit()))
+                .unwrap_or_else(|| "n/a".to_string());
+
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:<9}", label),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!("{} ", alert.rule.name), theme.value()),
+                Span::styled(
+                    format!("({}) ", alert.rule.condition),
+                    Style::default().fg(theme.secondary()),
+                ),
+                Span::styled(value, theme.value()),
+                Span::styled(duration, Style::default().fg(color)),
+            ]))
+        })
+        .collect();
+
+    if items.is_empty() {
+        items.push(ListItem::new(Span::styled(
+            "No alert rules configured",
+            Style::default().fg(theme.border()),
+        )));
+    }
+
+    f.render_widget(
+        List::new(items).style(Style::default().bg(theme.bg())),
+        list_area,
+    );
+
+    let Some(log_area) = log_area else {
+        return;
+    };
+
+    let log: Vec<ListItem> = app
+        .actions
+        .log()
+        .into_iter()
+        .rev()
+        .map(|entry| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{} ", entry.at.format("%H:%M:%S")),
+                    Style::default().fg(theme.border()),
+                ),
+                Span::styled(entry.message, theme.value()),
+            ]))
+        })
+        .collect();
+    let log_block = Block::default()
+        .borders(Borders::TOP)
+        .border_style(Style::default().fg(theme.border()))
+        .title(Span::styled(
+            " Action log ",
+            Style::default().fg(theme.secondary()),
+        ));
+    f.render_widget(
+        List::new(log)
+            .block(log_block)
+            .style(Style::default().bg(theme.bg())),
+        log_area,
+    );
+}
+
+fn render_footer(f: &mut Frame, area: Rect, app: &App, size: Breakpoint) {
+    let theme = &app.config.theme;
+    let compact = size == Breakpoint::Compact;
+
+    let footer_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Min(30),
+            Constraint::Length(if compact { 0 } else { 20 }),
+        ])
+        .split(area);
+
+    // Each span's click action, if any, so the hints double as buttons.
+    let mut footer_spans = Vec::new();
+    let mut actions = Vec::new();
+    for screen in Screen::ALL {
+        let style = if screen == app.config.screen {
+            theme.selection()
+        } else {
+            Style::default().fg(theme.fg())
+        };
+        // Small terminals only spell out the active screen's name.
+        let title = match app.keymap.label(Action::Screen(screen)) {
+            Some(key) if compact && screen != app.config.screen => format!(" {} ", key),
+            Some(key) => format!(" {} {} ", key, screen.title()),
+            None => format!(" {} ", screen.title()),
+        };
+        footer_spans.push(Span::styled(title, style));
+        actions.push(Some(Action::Screen(screen)));
+    }
+    footer_spans.push(Span::styled(" │", Style::default().fg(theme.border())));
+    actions.push(None);
+
+    let key_style = Style::default()
+        .fg(theme.primary())
+        .add_modifier(Modifier::BOLD);
+    let theme_label = format!("Theme: {}", app.config.theme_choice.label());
+    let hints = [
+        (Action::Quit, "Quit"),
+        (Action::Refresh, "Refresh"),
+        (
+            Action::Pause,
+            if app.paused.is_some() {
+                "Resume"
+            } else {
+                "Pause"
+            },
+        ),
+        (Action::CycleTheme, theme_label.as_str()),
+        (Action::Zoom, if app.zoomed { "Unzoom" } else { "Zoom" }),
+        (Action::Help, "Help"),
+    ];
+    // Hints come from the keymap so they show whatever the keys are bound to.
+    for (action, label) in hints {
+        if compact && !matches!(action, Action::Quit | Action::Pause | Action::Help) {
+            continue;
+        }
+        let Some(key) = app.keymap.label(action) else {
+            continue;
+        };
+        footer_spans.push(Span::styled(format!(" [{}]", key), key_style));
+        footer_spans.push(Span::styled(
+            format!(" {} ", label),
+            Style::default().fg(theme.fg()),
+        ));
+        actions.extend([Some(action), Some(action)]);
+    }
+
+    if app.paused.is_some() {
+        footer_spans.push(Span::styled(
+            " PAUSED ",
+            theme.badge(theme.warning()).add_modifier(Modifier::BOLD),
+        ));
+        actions.push(Some(Action::Pause));
+    }
+    if !compact {
+        footer_spans.push(Span::styled(
+            format!(" ⟳ {} ", format_millis(app.config.refresh_rate)),
+            Style::default().fg(theme.secondary()),
+        ));
+        actions.push(None);
+    }
+
+    let mut x = footer_chunks[0].x + 1;
+    let y = footer_chunks[0].y + 1;
+    let right = footer_chunks[0].right().saturating_sub(1);
+    let mut regions = app.regions.borrow_mut();
+    for (span, action) in footer_spans.iter().zip(actions) {
+        let width = (span.width() as u16).min(right.saturating_sub(x));
+        if let Some(action) = action {
+            regions.footer.push((action, Rect::new(x, y, width, 1)));
+        }
+        x += width;
+    }
+    drop(regions);
+
+    // Briefly surface the latest status message or alert action so saves,
+    // hooks and dry runs are visible.
+    let status = app
+        .status
+        .as_ref()
+        .filter(|(at, _)| at.elapsed().as_secs() < NOTICE_SECS as u64)
+        .map(|(_, message)| message.clone());
+    let action = app
+        .actions
+        .log()
+        .last()
+        .filter(|entry| (chrono::Local::now() - entry.at).num_seconds() < NOTICE_SECS)
+        .map(|entry| entry.message.clone());
+    if let Some(message) = status.or(action) {
+        footer_spans.push(Span::styled(
+            format!(" │ {}", message),
+            Style::default().fg(theme.secondary()),
+        ));
+    }
+
+    let footer_text = Line::from(footer_spans);
+
+    let footer = Paragraph::new(footer_text)
+        .style(Style::default().bg(theme.bg()).fg(theme.fg()))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(if app.config.rounded_borders {
+                    ratatui::widgets::BorderType::Rounded
+                } else {
+                    ratatui::widgets::BorderType::Plain
+                })
+                .border_style(Style::default().fg(theme.border())),
+        );
+
+    let credit_text = Line::from(vec![
+        Span::styled(" made by ", Style::default().fg(theme.fg())),
+        Span::styled("gui기กีギ", Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD)),
+    ]);
+
+    let credit = Paragraph::new(credit_text)
+        .style(Style::default().bg(theme.bg()).fg(theme.fg()))
+        .alignment(ratatui::layout::Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(if app.config.rounded_borders {
+                    ratatui::widgets::BorderType::Rounded
+                } else {
+                    ratatui::widgets::BorderType::Plain
+                })
+                .border_style(Style::default().fg(theme.border())),
+        );
+
+    f.render_widget(footer, footer_chunks[0]);
+    if !compact {
+        f.render_widget(credit, footer_chunks[1]);
+    }
+}